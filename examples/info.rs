@@ -1,5 +1,3 @@
-use systeminfo;
-
 fn main() {
     let sysinfo = systeminfo::from_system_hardware();
     println!("{:#?}", sysinfo);
@@ -0,0 +1,65 @@
+// Not every test module uses every helper
+#![allow(dead_code)]
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Temporary directory tree standing in for `/`, `/proc` or `/sys` in tests.
+/// The tree is removed when the fixture is dropped, also when a test fails.
+pub(crate) struct Fixture {
+    root: PathBuf,
+}
+
+impl Fixture {
+    pub fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!(
+            "systeminfo-{}-{}-{}",
+            name,
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&root).unwrap();
+        Self { root }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn path<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+        //! Path below the root. Absolute paths (eg: built from `root()`) are kept as is.
+        self.root.join(path)
+    }
+
+    pub fn dir<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+        let path = self.path(path);
+        fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    pub fn write<P: AsRef<Path>, C: AsRef<[u8]>>(&self, path: P, content: C) -> PathBuf {
+        //! Write a file, creating its parent directories
+        let path = self.path(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    pub fn symlink<T: AsRef<Path>, P: AsRef<Path>>(&self, target: T, path: P) -> PathBuf {
+        let path = self.path(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::os::unix::fs::symlink(target, &path).unwrap();
+        path
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
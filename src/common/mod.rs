@@ -1,13 +1,14 @@
 pub(crate) mod ip;
 pub(crate) mod utils;
 
-#[cfg(not(target_os = "linux"))]
 mod version;
-#[cfg(not(target_os = "linux"))]
-pub(crate) use self::version::Version;
+pub use self::version::Version;
 
 mod os_arch;
 pub(crate) use self::os_arch::OSArchitecture;
 
 #[cfg(unix)]
 pub(crate) mod hostname;
+
+#[cfg(all(test, unix))]
+pub(crate) mod fixture;
@@ -1,5 +1,7 @@
 #![allow(dead_code)]
 use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
 use std::process::Command;
 
 type Bytes = [u8];
@@ -140,3 +142,18 @@ pub fn to_hashmap_with_delim(string: String, delim: char) -> HashMap<String, Str
         })
         .collect()
 }
+
+pub fn read_file<P: AsRef<Path>>(path: P) -> Option<String> {
+    //! Reads a (usually procfs/sysfs) file and returns its trimmed
+    //! contents. Returns `None` when the file cannot be read or is empty.
+    //!
+    //! ## Example usage:
+    //! ```ignore
+    //! let governor = read_file("/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor");
+    //! println!("{:?}", governor);
+    //! ```
+    fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
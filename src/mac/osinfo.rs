@@ -26,6 +26,7 @@ pub fn from_system_os() -> SystemOS {
         architecture: OSArchitecture::get_arch().to_string(),
         hostname: hostname::get().unwrap_or_default(),
         ip_address: get_local_ip().unwrap_or_default(),
+        ..Default::default()
     }
 }
 
@@ -0,0 +1,355 @@
+use super::kernel::{kernel, KernelInfo};
+use crate::common::utils::read_file;
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, collections::BTreeMap, fs, path::Path};
+
+/// `LoaderInfo` EFI variable set by systemd-boot
+const LOADER_INFO: &str =
+    "sys/firmware/efi/efivars/LoaderInfo-4a67b082-0a4c-41cf-b6c7-440b29bb8c4f";
+
+/// Kernel image prefixes in `/boot`
+const IMAGE_PREFIXES: [&str; 4] = ["vmlinuz-", "vmlinux-", "Image-", "zImage-"];
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum Bootloader {
+    Grub,
+    SystemdBoot,
+    Refind,
+    /// extlinux/syslinux
+    Extlinux,
+    UBoot,
+    #[default]
+    Unknown,
+}
+
+/// A kernel installed in `/boot` or `/usr/lib/modules`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct InstalledKernel {
+    /// eg: `6.1.0-18-amd64`
+    pub release: String,
+    pub image: Option<String>,
+    pub initrd: Option<String>,
+    pub modules_dir: Option<String>,
+    pub running: bool,
+}
+
+/// A Boot Loader Specification entry from `loader/entries/*.conf`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BootEntry {
+    /// File name without the `.conf` suffix
+    pub id: String,
+    pub title: Option<String>,
+    pub version: Option<String>,
+    pub machine_id: Option<String>,
+    pub sort_key: Option<String>,
+    pub linux: Option<String>,
+    pub initrd: Vec<String>,
+    /// Kernel command line, multiple `options` lines joined
+    pub options: Option<String>,
+}
+
+/// Installed kernels and bootloader configuration
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct BootInfo {
+    pub bootloader: Bootloader,
+    /// Bootloader version, when the bootloader reports it (systemd-boot)
+    pub bootloader_version: Option<String>,
+    /// Installed kernels, oldest first
+    pub kernels: Vec<InstalledKernel>,
+    pub running_kernel: Option<String>,
+    pub entries: Vec<BootEntry>,
+    /// A kernel newer than the running one is installed,
+    /// or the system flagged it (`/run/reboot-required`)
+    pub reboot_required: bool,
+}
+
+pub fn boot_info() -> BootInfo {
+    //! Get the installed kernels, the bootloader and its Boot Loader
+    //! Specification entries, and whether a reboot into a newer kernel is pending.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let boot = systeminfo::boot_info();
+    //! println!("{:?}, reboot required: {}", boot.bootloader, boot.reboot_required);
+    //! ```
+    from_root(Path::new("/"), kernel().map(|k| k.release))
+}
+
+fn from_root(root: &Path, running: Option<String>) -> BootInfo {
+    let boot = root.join("boot");
+    let mut kernels: BTreeMap<String, InstalledKernel> = BTreeMap::new();
+
+    let boot_files: Vec<String> = fs::read_dir(&boot)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| e.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    for file in &boot_files {
+        let release = IMAGE_PREFIXES.iter().find_map(|p| file.strip_prefix(p));
+        // vmlinuz-0-rescue-<machine-id> is the RHEL/Fedora rescue image, not a kernel release
+        if let Some(release) = release
+            .filter(|r| r.starts_with(|c: char| c.is_ascii_digit()))
+            .filter(|r| !r.starts_with("0-rescue-"))
+        {
+            kernels.entry(release.to_string()).or_default().image =
+                Some(boot.join(file).display().to_string());
+        }
+    }
+    for modules in ["usr/lib/modules", "lib/modules"] {
+        let dir = root.join(modules);
+        let releases = fs::read_dir(&dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .filter_map(|e| e.file_name().into_string().ok())
+                    .collect::<Vec<String>>()
+            })
+            .unwrap_or_default();
+        for release in releases {
+            let kernel = kernels.entry(release.clone()).or_default();
+            if kernel.modules_dir.is_none() {
+                kernel.modules_dir = Some(dir.join(&release).display().to_string());
+            }
+            // Fedora and Arch also ship the image inside the modules directory
+            let image = dir.join(&release).join("vmlinuz");
+            if kernel.image.is_none() && image.is_file() {
+                kernel.image = Some(image.display().to_string());
+            }
+        }
+    }
+
+    let mut kernels: Vec<InstalledKernel> = kernels
+        .into_iter()
+        .map(|(release, mut kernel)| {
+            // initrd.img-<release> (Debian), initramfs-<release>.img (RHEL),
+            // initrd-<release> (SUSE)
+            kernel.initrd = [
+                format!("initrd.img-{}", release),
+                format!("initramfs-{}.img", release),
+                format!("initrd-{}", release),
+            ]
+            .iter()
+            .find(|f| boot_files.contains(f))
+            .map(|f| boot.join(f).display().to_string());
+            kernel.running = running.as_deref() == Some(release.as_str());
+            kernel.release = release;
+            kernel
+        })
+        // Leftover modules of removed kernels have no image
+        .filter(|k| k.image.is_some() || k.running)
+        .collect();
+    kernels.sort_by(|a, b| compare_releases(&a.release, &b.release));
+
+    // Only a newer kernel of the running flavor is booted by default,
+    // eg: an installed `-lowlatency` kernel does not replace `-generic`
+    let newer_installed = running.as_deref().is_some_and(|running| {
+        let flavor = KernelInfo::from_release(running).flavor;
+        kernels.iter().any(|k| {
+            KernelInfo::from_release(&k.release).flavor == flavor
+                && compare_releases(&k.release, running) == Ordering::Greater
+        })
+    });
+    let (bootloader, bootloader_version) = detect_bootloader(root);
+
+    BootInfo {
+        bootloader,
+        bootloader_version,
+        kernels,
+        running_kernel: running,
+        entries: boot_entries(root),
+        reboot_required: newer_installed || root.join("run/reboot-required").exists(),
+    }
+}
+
+fn compare_releases(a: &str, b: &str) -> Ordering {
+    let (ka, kb) = (KernelInfo::from_release(a), KernelInfo::from_release(b));
+    ka.cmp_version(&kb).then_with(|| {
+        // Same upstream version, compare the distribution ABI numerically when possible
+        let abi = |k: &KernelInfo| {
+            k.abi
+                .as_deref()
+                .and_then(|a| a.split('.').next())
+                .and_then(|a| a.parse::<u64>().ok())
+        };
+        abi(&ka).cmp(&abi(&kb)).then_with(|| a.cmp(b))
+    })
+}
+
+fn detect_bootloader(root: &Path) -> (Bootloader, Option<String>) {
+    // UCS-2 string after the 4 byte attributes, eg: `systemd-boot 254.5-1`
+    let loader_info = fs::read(root.join(LOADER_INFO)).ok().map(|v| {
+        let units: Vec<u16> = v
+            .get(4..)
+            .unwrap_or_default()
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|&u| u != 0)
+            .collect();
+        String::from_utf16_lossy(&units)
+    });
+    if let Some(version) = loader_info
+        .as_deref()
+        .and_then(|i| i.strip_prefix("systemd-boot "))
+    {
+        return (Bootloader::SystemdBoot, Some(version.to_string()));
+    }
+
+    let exists = |paths: &[&str]| paths.iter().any(|p| root.join(p).exists());
+    let esp_has = |file: &str| {
+        ["boot/efi/EFI", "efi/EFI", "boot/EFI"].iter().any(|esp| {
+            fs::read_dir(root.join(esp))
+                .map(|dirs| {
+                    dirs.filter_map(|d| d.ok())
+                        .any(|d| d.path().join(file).exists())
+                })
+                .unwrap_or_default()
+        })
+    };
+    let bootloader = if exists(&[
+        "boot/loader/loader.conf",
+        "efi/loader/loader.conf",
+        "boot/efi/loader/loader.conf",
+    ]) {
+        Bootloader::SystemdBoot
+    } else if esp_has("refind.conf") || exists(&["boot/refind_linux.conf"]) {
+        Bootloader::Refind
+    } else if exists(&["boot/grub/grub.cfg", "boot/grub2/grub.cfg"]) || esp_has("grub.cfg") {
+        Bootloader::Grub
+    } else if exists(&["boot/extlinux/extlinux.conf", "boot/syslinux/syslinux.cfg"]) {
+        Bootloader::Extlinux
+    } else if exists(&["boot/boot.scr", "boot/boot.cmd", "boot/uEnv.txt"]) {
+        Bootloader::UBoot
+    } else {
+        Bootloader::Unknown
+    };
+    (bootloader, None)
+}
+
+fn boot_entries(root: &Path) -> Vec<BootEntry> {
+    let mut entries: Vec<BootEntry> = [
+        "boot/loader/entries",
+        "efi/loader/entries",
+        "boot/efi/loader/entries",
+    ]
+    .iter()
+    .filter_map(|dir| fs::read_dir(root.join(dir)).ok())
+    .flat_map(|dir| dir.filter_map(|e| e.ok()))
+    .filter_map(|e| {
+        let id = e
+            .file_name()
+            .into_string()
+            .ok()?
+            .strip_suffix(".conf")?
+            .to_string();
+        Some(parse_boot_entry(id, &read_file(e.path())?))
+    })
+    .collect();
+    entries.sort_by(|a, b| a.id.cmp(&b.id));
+    entries.dedup_by(|a, b| a.id == b.id);
+    entries
+}
+
+fn parse_boot_entry(id: String, content: &str) -> BootEntry {
+    let mut entry = BootEntry {
+        id,
+        ..Default::default()
+    };
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = match line.split_once(char::is_whitespace) {
+            Some((key, value)) => (key, value.trim().to_string()),
+            None => continue,
+        };
+        match key {
+            "title" => entry.title = Some(value),
+            "version" => entry.version = Some(value),
+            "machine-id" => entry.machine_id = Some(value),
+            "sort-key" => entry.sort_key = Some(value),
+            "linux" => entry.linux = Some(value),
+            "initrd" => entry.initrd.push(value),
+            "options" => {
+                entry.options = Some(match entry.options.take() {
+                    Some(options) => format!("{} {}", options, value),
+                    None => value,
+                })
+            }
+            _ => {}
+        }
+    }
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixture::Fixture;
+
+    #[test]
+    fn test_boot_info() {
+        let fixture = Fixture::new("boot");
+        let root = fixture.root();
+        let boot = root.join("boot");
+        fixture.dir(boot.join("grub"));
+        fixture.dir(boot.join("loader/entries"));
+        for file in [
+            "vmlinuz-6.1.0-17-amd64",
+            "initrd.img-6.1.0-17-amd64",
+            "vmlinuz-6.1.0-9-amd64",
+            "vmlinuz-6.1.0-18-amd64",
+            "vmlinuz-0-rescue-4b2c8e6f1a9d4e0b8c7d6e5f4a3b2c1d",
+            "config-6.1.0-18-amd64",
+            "grub/grub.cfg",
+        ] {
+            fixture.write(boot.join(file), "");
+        }
+        fixture.dir(root.join("lib/modules/6.1.0-17-amd64"));
+        fixture.dir(root.join("lib/modules/5.10.0-1-amd64"));
+        fixture.write(
+            boot.join("loader/entries/abc-6.1.0-18-amd64.conf"),
+            "title Debian\nversion 6.1.0-18-amd64\nlinux /vmlinuz-6.1.0-18-amd64\n\
+             initrd /initrd.img-6.1.0-18-amd64\noptions root=/dev/sda1\noptions quiet\n",
+        );
+
+        let info = from_root(root, Some("6.1.0-17-amd64".into()));
+
+        assert_eq!(info.bootloader, Bootloader::Grub);
+        let releases: Vec<&str> = info.kernels.iter().map(|k| k.release.as_str()).collect();
+        assert_eq!(
+            releases,
+            vec!["6.1.0-9-amd64", "6.1.0-17-amd64", "6.1.0-18-amd64"]
+        );
+        assert!(info.kernels[1].running);
+        assert!(info.kernels[1].initrd.is_some());
+        assert!(info.kernels[1].modules_dir.is_some());
+        assert!(info.reboot_required);
+        assert_eq!(info.entries.len(), 1);
+        assert_eq!(
+            info.entries[0].options.as_deref(),
+            Some("root=/dev/sda1 quiet")
+        );
+    }
+
+    #[test]
+    fn test_reboot_required_by_flavor() {
+        let fixture = Fixture::new("flavors");
+        let root = fixture.root();
+        for file in [
+            "vmlinuz-6.8.0-40-generic",
+            "vmlinuz-6.8.0-45-lowlatency",
+            "vmlinuz-6.6.30-1-lts",
+            "vmlinuz-6.9.1-arch1-1",
+        ] {
+            fixture.write(root.join("boot").join(file), "");
+        }
+
+        assert!(!from_root(root, Some("6.8.0-40-generic".into())).reboot_required);
+        assert!(!from_root(root, Some("6.6.30-1-lts".into())).reboot_required);
+        assert!(from_root(root, Some("6.8.0-38-lowlatency".into())).reboot_required);
+        assert!(from_root(root, Some("6.9.0-arch1-1".into())).reboot_required);
+    }
+}
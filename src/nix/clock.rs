@@ -0,0 +1,496 @@
+use crate::common::utils::read_file;
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Maximum error from which systemd considers the clock unsynchronized, in microseconds
+const MAX_SYNC_ERROR: i64 = 16_000_000;
+
+/// Timezone of the system, from `/etc/localtime`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Timezone {
+    /// IANA name, eg: `Europe/Berlin`
+    pub name: Option<String>,
+    /// Current abbreviation, eg: `CEST`
+    pub abbreviation: Option<String>,
+    /// Current offset in seconds east of UTC
+    pub utc_offset: i32,
+    /// Daylight saving time is in effect
+    pub dst: bool,
+}
+
+/// Kernel clock state returned by `adjtimex(2)`
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum ClockState {
+    Ok,
+    /// A leap second will be inserted at the end of the day
+    Insert,
+    /// A leap second will be deleted at the end of the day
+    Delete,
+    /// Leap second in progress
+    InProgress,
+    /// A leap second has occurred
+    Wait,
+    /// The clock is not synchronized
+    #[default]
+    Error,
+}
+
+/// System clock, hardware clock and synchronization status
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClockInfo {
+    pub timezone: Timezone,
+    /// Hardware clock time, in UTC or local time depending on `rtc_local_time`
+    pub rtc_time: Option<NaiveDateTime>,
+    /// The hardware clock keeps local time (`LOCAL` in `/etc/adjtime`)
+    pub rtc_local_time: bool,
+    /// An NTP daemon (chrony, ntpd, systemd-timesyncd) synchronizes the kernel clock
+    pub synchronized: bool,
+    pub state: ClockState,
+    /// Remaining offset being corrected, in microseconds
+    pub offset_us: i64,
+    /// Maximum error, in microseconds
+    pub maxerror_us: i64,
+    /// Estimated error, in microseconds
+    pub esterror_us: i64,
+}
+
+pub fn timezone() -> Timezone {
+    //! Get the configured timezone, with its current offset and DST state
+    //! from the TZif data of `/etc/localtime`.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let tz = systeminfo::timezone();
+    //! println!("{:?} {:?} UTC{:+}", tz.name, tz.abbreviation, tz.utc_offset / 3600);
+    //! ```
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default();
+    timezone_from_root(Path::new("/"), now)
+}
+
+pub fn clock() -> ClockInfo {
+    //! Get the timezone, the hardware clock and the kernel clock
+    //! synchronization status, as reported by `timedatectl`.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let clock = systeminfo::clock();
+    //! if !clock.synchronized {
+    //!     println!("clock not synchronized, max error {}us", clock.maxerror_us);
+    //! }
+    //! ```
+    let root = Path::new("/");
+    // modes = 0 only reads the kernel clock and needs no privileges
+    let mut tx: libc::timex = unsafe { std::mem::zeroed() };
+    let ret = unsafe { libc::adjtimex(&mut tx) };
+    let state = match ret {
+        libc::TIME_OK => ClockState::Ok,
+        libc::TIME_INS => ClockState::Insert,
+        libc::TIME_DEL => ClockState::Delete,
+        libc::TIME_OOP => ClockState::InProgress,
+        libc::TIME_WAIT => ClockState::Wait,
+        _ => ClockState::Error,
+    };
+    let maxerror_us = tx.maxerror as i64;
+    let offset = tx.offset as i64;
+    let rtc = root.join("sys/class/rtc/rtc0");
+
+    ClockInfo {
+        timezone: timezone(),
+        rtc_time: read_file(rtc.join("date"))
+            .zip(read_file(rtc.join("time")))
+            .and_then(|(date, time)| {
+                NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M:%S")
+                    .ok()
+            }),
+        rtc_local_time: rtc_local_time(root),
+        synchronized: ret >= 0
+            && state != ClockState::Error
+            && tx.status & libc::STA_UNSYNC == 0
+            && maxerror_us < MAX_SYNC_ERROR,
+        state,
+        offset_us: if tx.status & libc::STA_NANO != 0 {
+            offset / 1000
+        } else {
+            offset
+        },
+        maxerror_us,
+        esterror_us: tx.esterror as i64,
+    }
+}
+
+fn rtc_local_time(root: &Path) -> bool {
+    // drift factor, last adjust time, last calibration time, then UTC or LOCAL
+    read_file(root.join("etc/adjtime"))
+        .and_then(|adjtime| adjtime.lines().nth(2).map(|l| l.trim() == "LOCAL"))
+        .unwrap_or_default()
+}
+
+fn timezone_from_root(root: &Path, now: i64) -> Timezone {
+    let localtime = root.join("etc/localtime");
+    // eg: /usr/share/zoneinfo/Europe/Berlin or ../usr/share/zoneinfo/UTC
+    let name = fs::read_link(&localtime)
+        .ok()
+        .and_then(|target| {
+            let target = target.display().to_string();
+            target
+                .rsplit_once("zoneinfo/")
+                .map(|(_, name)| name.to_string())
+        })
+        .or_else(|| {
+            read_file(root.join("etc/timezone")).and_then(|tz| tz.lines().next().map(String::from))
+        });
+    match fs::read(&localtime)
+        .ok()
+        .and_then(|data| parse_tzif(&data, now))
+    {
+        Some((abbreviation, utc_offset, dst)) => Timezone {
+            name,
+            abbreviation: Some(abbreviation),
+            utc_offset,
+            dst,
+        },
+        // No /etc/localtime means UTC
+        None => Timezone {
+            abbreviation: name.is_none().then(|| "UTC".to_string()),
+            name,
+            ..Default::default()
+        },
+    }
+}
+
+fn parse_tzif(data: &[u8], now: i64) -> Option<(String, i32, bool)> {
+    //! Local time type at `now` from TZif data, see RFC 8536
+    if data.get(..4)? != b"TZif" {
+        return None;
+    }
+    let u32_at = |pos: usize| {
+        data.get(pos..pos + 4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as usize)
+    };
+    // isutcnt, isstdcnt, leapcnt, timecnt, typecnt, charcnt
+    let counts = |header: usize| -> Option<[usize; 6]> {
+        let mut counts = [0; 6];
+        for (i, count) in counts.iter_mut().enumerate() {
+            *count = u32_at(header + 20 + i * 4)?;
+        }
+        Some(counts)
+    };
+
+    let [isut, isstd, leap, timecnt, typecnt, charcnt] = counts(0)?;
+    let (mut pos, mut time_size) = (44, 4);
+    let mut counts_v = [isut, isstd, leap, timecnt, typecnt, charcnt];
+    // Version 2+ repeats the data with 64 bit times after the version 1 block
+    if data[4] >= b'2' {
+        pos += timecnt * 5 + typecnt * 6 + charcnt + leap * 8 + isstd + isut;
+        counts_v = counts(pos)?;
+        pos += 44;
+        time_size = 8;
+    }
+    let [isut, isstd, leap, timecnt, typecnt, charcnt] = counts_v;
+
+    let times: Vec<i64> = (0..timecnt)
+        .map(|i| {
+            let b = data.get(pos + i * time_size..pos + (i + 1) * time_size)?;
+            Some(match time_size {
+                4 => i32::from_be_bytes([b[0], b[1], b[2], b[3]]) as i64,
+                _ => i64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]),
+            })
+        })
+        .collect::<Option<_>>()?;
+    let indices = pos + timecnt * time_size;
+    let types = indices + timecnt;
+    let chars = types + typecnt * 6;
+    let end = chars + charcnt + leap * (time_size + 4) + isstd + isut;
+
+    let ttinfo = |i: usize| -> Option<(String, i32, bool)> {
+        let t = data.get(types + i * 6..types + i * 6 + 6)?;
+        let abbreviation = data.get(chars + t[5] as usize..chars + charcnt)?;
+        let len = abbreviation.iter().position(|&b| b == 0)?;
+        Some((
+            String::from_utf8_lossy(&abbreviation[..len]).into_owned(),
+            i32::from_be_bytes([t[0], t[1], t[2], t[3]]),
+            t[4] != 0,
+        ))
+    };
+    // Footer with a POSIX TZ string for times after the last transition, eg: `\nCET-1CEST,M3.5.0,M10.5.0/3\n`
+    let footer = data
+        .get(end..)
+        .and_then(|f| std::str::from_utf8(f).ok())
+        .and_then(|f| f.split('\n').nth(1))
+        .filter(|f| !f.is_empty())
+        .and_then(PosixTz::parse);
+
+    match (times.iter().rposition(|&t| t <= now), footer) {
+        (Some(i), Some(tz)) if i + 1 == times.len() => Some(tz.local_time_type(now)),
+        (None, Some(tz)) if times.is_empty() => Some(tz.local_time_type(now)),
+        (Some(i), _) => ttinfo(*data.get(indices + i)? as usize),
+        (None, _) => ttinfo(0),
+    }
+}
+
+/// Transition date of a POSIX TZ rule
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum RuleDate {
+    /// `Mm.w.d`: day `d` (0 is Sunday) of week `w` (5 is the last) of month `m`
+    Month(u32, u32, u32),
+    /// `Jn`: day 1 to 365, February 29 is never counted
+    Julian(u32),
+    /// `n`: day 0 to 365, counting February 29
+    Day(u32),
+}
+
+impl RuleDate {
+    fn date(self, year: i32) -> Option<NaiveDate> {
+        match self {
+            RuleDate::Month(month, week, weekday) => {
+                let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+                let mut day = 1 + (weekday + 7 - first.weekday().num_days_from_sunday()) % 7;
+                day += (week - 1) * 7;
+                while NaiveDate::from_ymd_opt(year, month, day).is_none() {
+                    day -= 7;
+                }
+                NaiveDate::from_ymd_opt(year, month, day)
+            }
+            RuleDate::Julian(day) => {
+                let leap = NaiveDate::from_ymd_opt(year, 2, 29).is_some();
+                NaiveDate::from_yo_opt(year, day + (leap && day >= 60) as u32)
+            }
+            RuleDate::Day(day) => NaiveDate::from_yo_opt(year, day + 1),
+        }
+    }
+}
+
+/// POSIX TZ string, eg: `CET-1CEST,M3.5.0,M10.5.0/3`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PosixTz {
+    std: (String, i32),
+    dst: Option<(String, i32)>,
+    /// DST start and end, with the local time of the transition in seconds
+    rules: Option<((RuleDate, i32), (RuleDate, i32))>,
+}
+
+impl PosixTz {
+    fn parse(tz: &str) -> Option<Self> {
+        let (std_name, rest) = tz_name(tz)?;
+        let (std_offset, rest) = tz_offset(rest)?;
+        // POSIX offsets are west of UTC
+        let std = (std_name, -std_offset);
+        if rest.is_empty() {
+            return Some(Self {
+                std,
+                dst: None,
+                rules: None,
+            });
+        }
+        let (dst_name, rest) = tz_name(rest)?;
+        let (dst_offset, rest) = match tz_offset(rest) {
+            Some((offset, rest)) => (-offset, rest),
+            None => (std.1 + 3600, rest),
+        };
+        // Without rules, use the US rules like glibc
+        let rules = rest.strip_prefix(',').unwrap_or("M3.2.0,M11.1.0");
+        let (start, end) = rules.split_once(',')?;
+        Some(Self {
+            std,
+            dst: Some((dst_name, dst_offset)),
+            rules: Some((tz_rule(start)?, tz_rule(end)?)),
+        })
+    }
+
+    fn local_time_type(&self, now: i64) -> (String, i32, bool) {
+        let standard = (self.std.0.clone(), self.std.1, false);
+        let (dst, (start, end)) = match (&self.dst, self.rules) {
+            (Some(dst), Some(rules)) => (dst, rules),
+            _ => return standard,
+        };
+        let year = match DateTime::from_timestamp(now + self.std.1 as i64, 0) {
+            Some(local) => local.year(),
+            None => return standard,
+        };
+        // Transition instants in UTC, the start is given in standard time, the end in DST
+        let instant = |(date, time): (RuleDate, i32), offset: i32| {
+            date.date(year)
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|d| d.and_utc().timestamp() + time as i64 - offset as i64)
+        };
+        let (start, end) = match (instant(start, self.std.1), instant(end, dst.1)) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return standard,
+        };
+        // The southern hemisphere has DST over the new year
+        let in_dst = if start < end {
+            start <= now && now < end
+        } else {
+            now >= start || now < end
+        };
+        if in_dst {
+            (dst.0.clone(), dst.1, true)
+        } else {
+            standard
+        }
+    }
+}
+
+fn tz_name(tz: &str) -> Option<(String, &str)> {
+    //! Alphabetic name, or quoted like `<+03>`
+    let (name, rest) = match tz.strip_prefix('<') {
+        Some(quoted) => {
+            let (name, rest) = quoted.split_once('>')?;
+            (name, rest)
+        }
+        None => {
+            let len = tz
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(tz.len());
+            tz.split_at(len)
+        }
+    };
+    (name.len() >= 3).then(|| (name.to_string(), rest))
+}
+
+fn tz_offset(tz: &str) -> Option<(i32, &str)> {
+    //! `[+-]hh[:mm[:ss]]` in seconds
+    let (sign, digits) = match tz.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, tz.strip_prefix('+').unwrap_or(tz)),
+    };
+    let len = digits
+        .find(|c: char| !c.is_ascii_digit() && c != ':')
+        .unwrap_or(digits.len());
+    if len == 0 {
+        return None;
+    }
+    let seconds = digits[..len]
+        .split(':')
+        .zip([3600, 60, 1])
+        .map(|(part, unit)| part.parse::<i32>().map(|p| p * unit))
+        .sum::<Result<i32, _>>()
+        .ok()?;
+    Some((sign * seconds, &digits[len..]))
+}
+
+fn tz_rule(rule: &str) -> Option<(RuleDate, i32)> {
+    //! `date[/time]`, the time defaults to 02:00
+    let (date, time) = match rule.split_once('/') {
+        Some((date, time)) => (date, tz_offset(time)?.0),
+        None => (rule, 7200),
+    };
+    let date = if let Some(m) = date.strip_prefix('M') {
+        let parts: Vec<u32> = m
+            .split('.')
+            .map(|p| p.parse().ok())
+            .collect::<Option<_>>()?;
+        match parts[..] {
+            [month @ 1..=12, week @ 1..=5, weekday @ 0..=6] => {
+                RuleDate::Month(month, week, weekday)
+            }
+            _ => return None,
+        }
+    } else if let Some(day) = date.strip_prefix('J') {
+        RuleDate::Julian(day.parse().ok().filter(|d| (1..=365).contains(d))?)
+    } else {
+        RuleDate::Day(date.parse().ok().filter(|d| *d <= 365)?)
+    };
+    Some((date, time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixture::Fixture;
+
+    fn tzif(times: &[i64], types: &[(i32, bool, u8)], chars: &str, footer: &str) -> Vec<u8> {
+        let header = |time_size: usize| {
+            let mut h = b"TZif2".to_vec();
+            h.extend([0; 15]);
+            for count in [0, 0, 0, times.len(), types.len(), chars.len()] {
+                h.extend((count as u32).to_be_bytes());
+            }
+            let mut body = Vec::new();
+            for t in times {
+                match time_size {
+                    4 => body.extend((*t as i32).to_be_bytes()),
+                    _ => body.extend(t.to_be_bytes()),
+                }
+            }
+            body.extend((0..times.len()).map(|i| (i % types.len()) as u8));
+            for (offset, dst, index) in types {
+                body.extend(offset.to_be_bytes());
+                body.extend([*dst as u8, *index]);
+            }
+            body.extend(chars.as_bytes());
+            [h, body].concat()
+        };
+        [header(4), header(8), format!("\n{}\n", footer).into_bytes()].concat()
+    }
+
+    #[test]
+    fn test_posix_tz() {
+        let berlin = PosixTz::parse("CET-1CEST,M3.5.0,M10.5.0/3").unwrap();
+        // 2026-07-01 and 2026-01-15
+        assert_eq!(
+            berlin.local_time_type(1_782_864_000),
+            ("CEST".into(), 7200, true)
+        );
+        assert_eq!(
+            berlin.local_time_type(1_768_435_200),
+            ("CET".into(), 3600, false)
+        );
+        // DST starts 2026-03-29 01:00 UTC
+        assert!(!berlin.local_time_type(1_774_745_999).2);
+        assert!(berlin.local_time_type(1_774_746_000).2);
+
+        let sydney = PosixTz::parse("AEST-10AEDT,M10.1.0,M4.1.0/3").unwrap();
+        assert!(sydney.local_time_type(1_768_435_200).2);
+        assert!(!sydney.local_time_type(1_782_864_000).2);
+
+        let kolkata = PosixTz::parse("IST-5:30").unwrap();
+        assert_eq!(kolkata.local_time_type(0), ("IST".into(), 19800, false));
+        let quoted = PosixTz::parse("<-03>3").unwrap();
+        assert_eq!(quoted.local_time_type(0), ("-03".into(), -10800, false));
+    }
+
+    #[test]
+    fn test_timezone_from_root() {
+        let fixture = Fixture::new("tz");
+        let root = fixture.root();
+        let zoneinfo = root.join("usr/share/zoneinfo/Europe");
+        fixture.dir(&zoneinfo);
+        fixture.dir(root.join("etc"));
+        // Two transitions to CET and CEST, the footer covers later times
+        let data = tzif(
+            &[-2_422_054_408, 1_000_000_000],
+            &[(3600, false, 4), (7200, true, 8), (3208, false, 0)],
+            "LMT\0CET\0CEST\0",
+            "CET-1CEST,M3.5.0,M10.5.0/3",
+        );
+        fixture.write(zoneinfo.join("Berlin"), &data);
+        fixture.symlink(
+            root.join("usr/share/zoneinfo/Europe/Berlin"),
+            root.join("etc/localtime"),
+        );
+        fixture.write(root.join("etc/adjtime"), "0.0 0 0.0\n0\nLOCAL\n");
+        let summer = timezone_from_root(root, 1_782_864_000);
+        let before = timezone_from_root(root, -3_000_000_000);
+        let local = rtc_local_time(root);
+
+        assert_eq!(summer.name.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(summer.abbreviation.as_deref(), Some("CEST"));
+        assert_eq!(summer.utc_offset, 7200);
+        assert!(summer.dst);
+        assert_eq!(before.abbreviation.as_deref(), Some("CET"));
+        assert!(local);
+        assert_eq!(
+            parse_tzif(&data, 1_500_000_000),
+            Some(("CEST".into(), 7200, true))
+        );
+    }
+}
@@ -0,0 +1,548 @@
+use super::dmi::Dmi;
+use crate::common::utils::read_file;
+use core::fmt::{Display, Formatter, Result};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+/// Link-local address of the instance metadata service of most providers
+const METADATA_BASE_URL: &str = "http://169.254.169.254";
+/// Alibaba Cloud serves its metadata from a different address
+const ALIBABA_METADATA_BASE_URL: &str = "http://100.100.100.200";
+
+/// Cloud provider the system is running on
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub enum CloudProvider {
+    /// Not running on a recognised cloud provider.
+    #[default]
+    None,
+    Aws,
+    Gcp,
+    Azure,
+    Oracle,
+    DigitalOcean,
+    Hetzner,
+    OpenStack,
+    Alibaba,
+    Linode,
+    Vultr,
+    Tencent,
+    Scaleway,
+    UpCloud,
+    Exoscale,
+}
+
+impl Display for CloudProvider {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match *self {
+            Self::None => write!(f, "none"),
+            Self::Aws => write!(f, "aws"),
+            Self::Gcp => write!(f, "gcp"),
+            Self::Azure => write!(f, "azure"),
+            Self::Oracle => write!(f, "oracle"),
+            Self::DigitalOcean => write!(f, "digitalocean"),
+            Self::Hetzner => write!(f, "hetzner"),
+            Self::OpenStack => write!(f, "openstack"),
+            Self::Alibaba => write!(f, "alibaba"),
+            Self::Linode => write!(f, "linode"),
+            Self::Vultr => write!(f, "vultr"),
+            Self::Tencent => write!(f, "tencent"),
+            Self::Scaleway => write!(f, "scaleway"),
+            Self::UpCloud => write!(f, "upcloud"),
+            Self::Exoscale => write!(f, "exoscale"),
+        }
+    }
+}
+
+/// Cloud provider and instance identification
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CloudInfo {
+    pub provider: CloudProvider,
+    pub instance_type: Option<String>,
+    pub instance_id: Option<String>,
+    /// Only known after probing the instance metadata service
+    pub region: Option<String>,
+}
+
+/// Opt-in client for the instance metadata service of the provider,
+/// used to fill in what cannot be derived from local DMI data.
+#[derive(Clone, Debug)]
+pub struct MetadataProbe {
+    base_url: Option<String>,
+    timeout: Duration,
+}
+
+/// Metadata paths of a provider, relative to the base URL
+struct Endpoints {
+    base_url: &'static str,
+    headers: &'static [(&'static str, &'static str)],
+    /// Request an IMDSv2 session token first
+    aws_token: bool,
+    instance_id: &'static str,
+    instance_type: Option<&'static str>,
+    region: Option<&'static str>,
+}
+
+pub fn cloud() -> CloudInfo {
+    //! Identify the cloud provider from local DMI data and `/sys/hypervisor/uuid`.
+    //! No network access is made, see [`MetadataProbe`] for that.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let cloud = systeminfo::cloud();
+    //! println!("{} {:?} {:?}", cloud.provider, cloud.instance_type, cloud.instance_id);
+    //! ```
+    detect(&Dmi::read(), read_file("/sys/hypervisor/uuid").as_deref())
+}
+
+fn detect(dmi: &Dmi, hypervisor_uuid: Option<&str>) -> CloudInfo {
+    let is = |field: &Option<String>, prefix: &str| {
+        field
+            .as_deref()
+            .is_some_and(|f| f.to_lowercase().starts_with(&prefix.to_lowercase()))
+    };
+
+    let provider = if is(&dmi.sys_vendor, "Amazon EC2")
+        || is(&dmi.bios_vendor, "Amazon EC2")
+        || dmi
+            .bios_version
+            .as_deref()
+            .is_some_and(|v| v.contains("amazon"))
+        || hypervisor_uuid.is_some_and(|u| u.to_lowercase().starts_with("ec2"))
+    {
+        CloudProvider::Aws
+    } else if is(&dmi.product_name, "Google Compute Engine") {
+        CloudProvider::Gcp
+    } else if is(&dmi.chassis_asset_tag, "7783-7084-3265-9085-8269-3286-77") {
+        // Azure sets this fixed asset tag on every VM
+        CloudProvider::Azure
+    } else if is(&dmi.chassis_asset_tag, "OracleCloud.com") {
+        CloudProvider::Oracle
+    } else if is(&dmi.sys_vendor, "DigitalOcean") {
+        CloudProvider::DigitalOcean
+    } else if is(&dmi.sys_vendor, "Hetzner") {
+        CloudProvider::Hetzner
+    } else if is(&dmi.sys_vendor, "Alibaba Cloud") || is(&dmi.product_name, "Alibaba Cloud") {
+        CloudProvider::Alibaba
+    } else if is(&dmi.product_name, "OpenStack") || is(&dmi.sys_vendor, "OpenStack") {
+        CloudProvider::OpenStack
+    } else if is(&dmi.sys_vendor, "Linode") || is(&dmi.sys_vendor, "Akamai") {
+        CloudProvider::Linode
+    } else if is(&dmi.sys_vendor, "Vultr") {
+        CloudProvider::Vultr
+    } else if is(&dmi.sys_vendor, "Tencent Cloud") {
+        CloudProvider::Tencent
+    } else if is(&dmi.sys_vendor, "Scaleway") {
+        CloudProvider::Scaleway
+    } else if is(&dmi.sys_vendor, "UpCloud") {
+        CloudProvider::UpCloud
+    } else if is(&dmi.sys_vendor, "Exoscale") || is(&dmi.product_name, "Exoscale") {
+        CloudProvider::Exoscale
+    } else {
+        CloudProvider::None
+    };
+
+    let (instance_type, instance_id) = match provider {
+        // Nitro instances expose the instance type as product name
+        // and the instance ID as board asset tag
+        CloudProvider::Aws => (
+            dmi.product_name.clone().filter(|p| p.contains('.')),
+            dmi.board_asset_tag.clone().filter(|t| t.starts_with("i-")),
+        ),
+        CloudProvider::Azure | CloudProvider::OpenStack => {
+            (None, dmi.product_uuid.as_ref().map(|u| u.to_lowercase()))
+        }
+        CloudProvider::DigitalOcean => (None, dmi.product_serial.clone()),
+        _ => (None, None),
+    };
+
+    CloudInfo {
+        provider,
+        instance_type,
+        instance_id,
+        region: None,
+    }
+}
+
+impl Default for MetadataProbe {
+    fn default() -> Self {
+        Self {
+            base_url: None,
+            timeout: Duration::from_secs(2),
+        }
+    }
+}
+
+impl MetadataProbe {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn base_url<S: Into<String>>(mut self, base_url: S) -> Self {
+        //! Use another metadata service address than the provider default,
+        //! eg: a local stand-in server for testing. Only `http://` is supported.
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        //! Connect and read timeout of each request. Defaults to 2 seconds.
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn probe(&self, info: &CloudInfo) -> CloudInfo {
+        //! Query the metadata service of `info.provider` and fill in the
+        //! instance ID, instance type and region. Values that cannot be
+        //! fetched are kept from `info`.
+        //! ## Example Usage:
+        //! ```ignore
+        //! use systeminfo::{cloud, MetadataProbe};
+        //! let cloud = MetadataProbe::new().probe(&cloud());
+        //! println!("{:#?}", cloud);
+        //! ```
+        let mut result = info.clone();
+        let endpoints = match endpoints(&info.provider) {
+            Some(endpoints) => endpoints,
+            None => return result,
+        };
+        let base_url = self
+            .base_url
+            .as_deref()
+            .unwrap_or(endpoints.base_url)
+            .trim_end_matches('/');
+
+        let token = if endpoints.aws_token {
+            http_request(
+                "PUT",
+                &format!("{}/latest/api/token", base_url),
+                &[("X-aws-ec2-metadata-token-ttl-seconds", "60")],
+                self.timeout,
+            )
+        } else {
+            None
+        };
+        let mut headers = endpoints.headers.to_vec();
+        if let Some(ref token) = token {
+            headers.push(("X-aws-ec2-metadata-token", token));
+        }
+        let get = |path: &str| {
+            http_request(
+                "GET",
+                &format!("{}{}", base_url, path),
+                &headers,
+                self.timeout,
+            )
+            .filter(|v| !v.is_empty())
+        };
+
+        if let Some(id) = get(endpoints.instance_id) {
+            result.instance_id = Some(id);
+        }
+        if let Some(instance_type) = endpoints.instance_type.and_then(get) {
+            // GCP returns `projects/<id>/machineTypes/<type>`
+            result.instance_type = instance_type.rsplit('/').next().map(Into::into);
+        }
+        if let Some(region) = endpoints.region.and_then(get) {
+            result.region = match info.provider {
+                // GCP only has the zone: `projects/<id>/zones/us-central1-a`
+                CloudProvider::Gcp => region
+                    .rsplit('/')
+                    .next()
+                    .and_then(|zone| zone.rsplit_once('-'))
+                    .map(|(region, _)| region.to_string()),
+                _ => Some(region),
+            };
+        }
+        result
+    }
+}
+
+fn endpoints(provider: &CloudProvider) -> Option<Endpoints> {
+    let endpoints = match provider {
+        CloudProvider::Aws | CloudProvider::OpenStack => Endpoints {
+            base_url: METADATA_BASE_URL,
+            headers: &[],
+            aws_token: *provider == CloudProvider::Aws,
+            instance_id: "/latest/meta-data/instance-id",
+            instance_type: Some("/latest/meta-data/instance-type"),
+            region: (*provider == CloudProvider::Aws)
+                .then_some("/latest/meta-data/placement/region"),
+        },
+        CloudProvider::Gcp => Endpoints {
+            base_url: METADATA_BASE_URL,
+            headers: &[("Metadata-Flavor", "Google")],
+            aws_token: false,
+            instance_id: "/computeMetadata/v1/instance/id",
+            instance_type: Some("/computeMetadata/v1/instance/machine-type"),
+            region: Some("/computeMetadata/v1/instance/zone"),
+        },
+        CloudProvider::Azure => Endpoints {
+            base_url: METADATA_BASE_URL,
+            headers: &[("Metadata", "true")],
+            aws_token: false,
+            instance_id: "/metadata/instance/compute/vmId?api-version=2021-02-01&format=text",
+            instance_type: Some(
+                "/metadata/instance/compute/vmSize?api-version=2021-02-01&format=text",
+            ),
+            region: Some("/metadata/instance/compute/location?api-version=2021-02-01&format=text"),
+        },
+        CloudProvider::Oracle => Endpoints {
+            base_url: METADATA_BASE_URL,
+            headers: &[("Authorization", "Bearer Oracle")],
+            aws_token: false,
+            instance_id: "/opc/v2/instance/id",
+            instance_type: Some("/opc/v2/instance/shape"),
+            region: Some("/opc/v2/instance/canonicalRegionName"),
+        },
+        CloudProvider::DigitalOcean => Endpoints {
+            base_url: METADATA_BASE_URL,
+            headers: &[],
+            aws_token: false,
+            instance_id: "/metadata/v1/id",
+            instance_type: None,
+            region: Some("/metadata/v1/region"),
+        },
+        CloudProvider::Hetzner => Endpoints {
+            base_url: METADATA_BASE_URL,
+            headers: &[],
+            aws_token: false,
+            instance_id: "/hetzner/v1/metadata/instance-id",
+            instance_type: None,
+            region: Some("/hetzner/v1/metadata/region"),
+        },
+        CloudProvider::Alibaba => Endpoints {
+            base_url: ALIBABA_METADATA_BASE_URL,
+            headers: &[],
+            aws_token: false,
+            instance_id: "/latest/meta-data/instance-id",
+            instance_type: Some("/latest/meta-data/instance/instance-type"),
+            region: Some("/latest/meta-data/region-id"),
+        },
+        _ => return None,
+    };
+    Some(endpoints)
+}
+
+fn http_request(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    timeout: Duration,
+) -> Option<String> {
+    //! Minimal HTTP/1.1 client for the metadata services, returns
+    //! the response body of a `200 OK` response.
+    let rest = url.strip_prefix("http://")?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let addr = if authority.contains(':') {
+        authority.to_socket_addrs()
+    } else {
+        (authority, 80).to_socket_addrs()
+    }
+    .ok()?
+    .next()?;
+
+    let mut stream = TcpStream::connect_timeout(&addr, timeout).ok()?;
+    stream.set_read_timeout(Some(timeout)).ok()?;
+    stream.set_write_timeout(Some(timeout)).ok()?;
+
+    let mut request = format!(
+        "{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\nContent-Length: 0\r\n",
+        method, path, authority
+    );
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).ok()?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).ok()?;
+    let response = String::from_utf8_lossy(&response);
+    let (head, body) = response.split_once("\r\n\r\n")?;
+    let mut lines = head.lines();
+    if lines.next()?.split_whitespace().nth(1)? != "200" {
+        return None;
+    }
+    let chunked = lines.any(|l| {
+        l.to_lowercase()
+            .replace(' ', "")
+            .starts_with("transfer-encoding:chunked")
+    });
+    let body = if chunked {
+        decode_chunked(body)?
+    } else {
+        body.to_string()
+    };
+    Some(body.trim().to_string())
+}
+
+fn decode_chunked(mut body: &str) -> Option<String> {
+    let mut decoded = String::new();
+    loop {
+        let (size, rest) = body.split_once("\r\n")?;
+        let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+        if size == 0 {
+            return Some(decoded);
+        }
+        decoded.push_str(rest.get(..size)?);
+        body = rest.get(size..)?.trim_start_matches("\r\n");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{io::BufRead, io::BufReader, net::TcpListener, thread};
+
+    fn serve(requests: usize, handler: fn(&str, &[String]) -> (u16, String, bool)) -> String {
+        //! Local stand-in for a metadata service, answering `requests` requests
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for stream in listener.incoming().take(requests) {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut headers = Vec::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    headers.push(line.trim().to_string());
+                }
+                let (status, body, chunked) = handler(request_line.trim(), &headers);
+                let response = if chunked {
+                    let (a, b) = body.split_at(body.len() / 2);
+                    format!(
+                        "HTTP/1.1 {} X\r\nTransfer-Encoding: chunked\r\n\r\n{:x}\r\n{}\r\n{:x}\r\n{}\r\n0\r\n\r\n",
+                        status,
+                        a.len(),
+                        a,
+                        b.len(),
+                        b
+                    )
+                } else {
+                    format!(
+                        "HTTP/1.1 {} X\r\nContent-Length: {}\r\n\r\n{}",
+                        status,
+                        body.len(),
+                        body
+                    )
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_detect_from_dmi() {
+        let dmi = Dmi {
+            sys_vendor: Some("Amazon EC2".to_string()),
+            product_name: Some("m5.large".to_string()),
+            board_asset_tag: Some("i-0123456789abcdef0".to_string()),
+            ..Default::default()
+        };
+        let aws = detect(&dmi, None);
+        assert_eq!(aws.provider, CloudProvider::Aws);
+        assert_eq!(aws.instance_type.as_deref(), Some("m5.large"));
+        assert_eq!(aws.instance_id.as_deref(), Some("i-0123456789abcdef0"));
+
+        let xen = detect(
+            &Dmi::default(),
+            Some("ec2e1916-9099-7caf-fd21-012345abcdef"),
+        );
+        assert_eq!(xen.provider, CloudProvider::Aws);
+        assert!(xen.instance_type.is_none());
+
+        let azure = Dmi {
+            sys_vendor: Some("Microsoft Corporation".to_string()),
+            chassis_asset_tag: Some("7783-7084-3265-9085-8269-3286-77".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(detect(&azure, None).provider, CloudProvider::Azure);
+
+        let gcp = Dmi {
+            product_name: Some("Google Compute Engine".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(detect(&gcp, None).provider.to_string(), "gcp");
+        // Chromebooks and other Google hardware
+        let chromebook = Dmi {
+            sys_vendor: Some("Google".to_string()),
+            product_name: Some("Lazor".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(detect(&chromebook, None).provider, CloudProvider::None);
+        assert_eq!(detect(&Dmi::default(), None), CloudInfo::default());
+    }
+
+    #[test]
+    fn test_probe_gcp() {
+        let base_url = serve(3, |request, headers| {
+            if !headers.iter().any(|h| h == "Metadata-Flavor: Google") {
+                return (403, String::new(), false);
+            }
+            match request.split_whitespace().nth(1).unwrap() {
+                "/computeMetadata/v1/instance/id" => (200, "4520031799277581759".into(), false),
+                "/computeMetadata/v1/instance/machine-type" => {
+                    (200, "projects/123/machineTypes/e2-medium".into(), true)
+                }
+                "/computeMetadata/v1/instance/zone" => {
+                    (200, "projects/123/zones/us-central1-a".into(), false)
+                }
+                _ => (404, String::new(), false),
+            }
+        });
+        let info = CloudInfo {
+            provider: CloudProvider::Gcp,
+            ..Default::default()
+        };
+        let info = MetadataProbe::new().base_url(base_url).probe(&info);
+        assert_eq!(info.instance_id.as_deref(), Some("4520031799277581759"));
+        assert_eq!(info.instance_type.as_deref(), Some("e2-medium"));
+        assert_eq!(info.region.as_deref(), Some("us-central1"));
+    }
+
+    #[test]
+    fn test_probe_aws_imdsv2() {
+        let base_url = serve(4, |request, headers| {
+            let authorized = headers
+                .iter()
+                .any(|h| h == "X-aws-ec2-metadata-token: t0k3n");
+            match (
+                request.split_whitespace().next().unwrap(),
+                request.split_whitespace().nth(1).unwrap(),
+            ) {
+                ("PUT", "/latest/api/token") => (200, "t0k3n".into(), false),
+                (_, _) if !authorized => (401, String::new(), false),
+                (_, "/latest/meta-data/instance-id") => (200, "i-0abc".into(), false),
+                (_, "/latest/meta-data/instance-type") => (200, "t3.micro".into(), false),
+                (_, "/latest/meta-data/placement/region") => (200, "eu-west-1".into(), false),
+                _ => (404, String::new(), false),
+            }
+        });
+        let info = CloudInfo {
+            provider: CloudProvider::Aws,
+            instance_type: Some("t3.micro".to_string()),
+            ..Default::default()
+        };
+        let info = MetadataProbe::new()
+            .base_url(base_url)
+            .timeout(Duration::from_secs(5))
+            .probe(&info);
+        assert_eq!(info.instance_id.as_deref(), Some("i-0abc"));
+        assert_eq!(info.instance_type.as_deref(), Some("t3.micro"));
+        assert_eq!(info.region.as_deref(), Some("eu-west-1"));
+    }
+}
@@ -0,0 +1,165 @@
+use crate::common::utils::read_file;
+use serde::{Deserialize, Serialize};
+
+/// Value of a kernel command line parameter
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ParamValue {
+    /// `on`/`off`, `yes`/`no`, `y`/`n` or `true`/`false`
+    Bool(bool),
+    Integer(i64),
+    String(String),
+}
+
+impl ParamValue {
+    fn parse(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "on" | "yes" | "y" | "true" => Self::Bool(true),
+            "off" | "no" | "n" | "false" => Self::Bool(false),
+            _ => match value.parse() {
+                Ok(n) => Self::Integer(n),
+                Err(_) => Self::String(value.to_string()),
+            },
+        }
+    }
+}
+
+/// A `key` or `key=value` kernel parameter
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct KernelParam {
+    /// Parameter name, module parameters are prefixed with the module (eg: `nvme.poll_queues`)
+    pub key: String,
+    /// `None` for flags like `quiet`
+    pub value: Option<ParamValue>,
+    /// Value as written, without the quotes
+    pub raw_value: Option<String>,
+}
+
+/// Parsed `/proc/cmdline`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct KernelCmdline {
+    pub raw: String,
+    pub params: Vec<KernelParam>,
+    /// Arguments after `--`, passed to init
+    pub init_args: Vec<String>,
+}
+
+impl KernelCmdline {
+    pub fn parse(cmdline: &str) -> Self {
+        //! Split a command line the way the kernel does: on whitespace,
+        //! with double quotes protecting spaces, eg: `dyndbg="file foo.c +p"`
+        let mut words = split_words(cmdline).into_iter();
+        let mut params = Vec::new();
+        for word in words.by_ref() {
+            if word == "--" {
+                break;
+            }
+            let param = match word.split_once('=') {
+                Some((key, value)) => KernelParam {
+                    key: key.to_string(),
+                    value: Some(ParamValue::parse(value)),
+                    raw_value: Some(value.to_string()),
+                },
+                None => KernelParam {
+                    key: word,
+                    value: None,
+                    raw_value: None,
+                },
+            };
+            params.push(param);
+        }
+        Self {
+            raw: cmdline.trim().to_string(),
+            params,
+            init_args: words.collect(),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<&KernelParam> {
+        //! Get a parameter, the last occurrence wins like in the kernel.
+        //! Dashes and underscores in names are equivalent.
+        let normalize = |k: &str| k.replace('-', "_");
+        let key = normalize(key);
+        self.params.iter().rev().find(|p| normalize(&p.key) == key)
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.get(key).is_some()
+    }
+}
+
+fn split_words(cmdline: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_quotes = false;
+    let mut started = false;
+    for c in cmdline.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                started = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if started {
+                    words.push(std::mem::take(&mut word));
+                    started = false;
+                }
+            }
+            c => {
+                word.push(c);
+                started = true;
+            }
+        }
+    }
+    if started {
+        words.push(word);
+    }
+    words
+}
+
+pub fn kernel_cmdline() -> Option<KernelCmdline> {
+    //! Get the parameters the running kernel was booted with.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let cmdline = systeminfo::kernel_cmdline().unwrap();
+    //! println!("root={:?}", cmdline.get("root").and_then(|p| p.raw_value.clone()));
+    //! ```
+    read_file("/proc/cmdline").map(|c| KernelCmdline::parse(&c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cmdline() {
+        let cmdline = KernelCmdline::parse(
+            "BOOT_IMAGE=/vmlinuz-6.1.0 root=UUID=0a1b ro quiet mitigations=off \
+             panic=-1 dyndbg=\"file drm.c +p\" \"acpi_osi=Windows 2020\" nvme.poll-queues=4 \
+             -- single --debug\n",
+        );
+        assert_eq!(
+            cmdline.get("root").unwrap().raw_value.as_deref(),
+            Some("UUID=0a1b")
+        );
+        assert_eq!(cmdline.get("quiet").unwrap().value, None);
+        assert_eq!(
+            cmdline.get("mitigations").unwrap().value,
+            Some(ParamValue::Bool(false))
+        );
+        assert_eq!(
+            cmdline.get("panic").unwrap().value,
+            Some(ParamValue::Integer(-1))
+        );
+        assert_eq!(
+            cmdline.get("dyndbg").unwrap().value,
+            Some(ParamValue::String("file drm.c +p".into()))
+        );
+        assert_eq!(
+            cmdline.get("acpi_osi").unwrap().raw_value.as_deref(),
+            Some("Windows 2020")
+        );
+        assert!(cmdline.contains("nvme.poll_queues"));
+        assert_eq!(cmdline.init_args, vec!["single", "--debug"]);
+    }
+}
@@ -0,0 +1,336 @@
+use super::process::{parse_cgroup, Cgroup};
+use crate::common::utils::{read_file, to_hashmap_with_delim};
+use core::fmt::{Display, Formatter, Result};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::Path};
+
+/// Container runtime the process is running in
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub enum ContainerRuntime {
+    /// Not running in a container.
+    #[default]
+    None,
+    Docker,
+    Podman,
+    Lxc,
+    SystemdNspawn,
+    Kubernetes,
+    Containerd,
+    CriO,
+    /// Windows Subsystem for Linux
+    Wsl,
+    /// A container was detected, but the runtime could not be identified.
+    Other(String),
+}
+
+impl Display for ContainerRuntime {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match *self {
+            Self::None => write!(f, "none"),
+            Self::Docker => write!(f, "docker"),
+            Self::Podman => write!(f, "podman"),
+            Self::Lxc => write!(f, "lxc"),
+            Self::SystemdNspawn => write!(f, "systemd-nspawn"),
+            Self::Kubernetes => write!(f, "kubernetes"),
+            Self::Containerd => write!(f, "containerd"),
+            Self::CriO => write!(f, "cri-o"),
+            Self::Wsl => write!(f, "wsl"),
+            Self::Other(ref name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Result of the container detection
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ContainerInfo {
+    pub runtime: ContainerRuntime,
+    /// Container ID (or name for LXC and systemd-nspawn)
+    pub id: Option<String>,
+    /// Image name, when the runtime exposes it inside the container
+    pub image: Option<String>,
+}
+
+impl ContainerInfo {
+    pub fn is_container(&self) -> bool {
+        self.runtime != ContainerRuntime::None
+    }
+}
+
+/// Raw hints the detection is based on
+#[derive(Default)]
+struct Hints {
+    dockerenv: bool,
+    /// `/run/.containerenv`, written by Podman
+    containerenv: Option<HashMap<String, String>>,
+    /// `container=` from the environment of PID 1, or `/run/systemd/container`
+    container_env: Option<String>,
+    kubernetes_env: bool,
+    cgroups: Vec<Cgroup>,
+    mountinfo: String,
+    proc_version: String,
+}
+
+pub fn container() -> ContainerInfo {
+    //! Detect whether the process runs inside a container,
+    //! and if so, the runtime, container ID and image.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let container = systeminfo::container();
+    //! println!("{} {:?}", container.runtime, container.id);
+    //! ```
+    let environ: HashMap<String, String> = fs::read("/proc/1/environ")
+        .map(|e| {
+            e.split(|&b| b == 0)
+                .filter_map(|kv| {
+                    let kv = String::from_utf8_lossy(kv);
+                    let (k, v) = kv.split_once('=')?;
+                    Some((k.to_string(), v.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    // PID 1 of another container can hide behind a PID namespace
+    // we cannot read, so our own environment and cgroup are used as fallback.
+    let cgroups = read_file("/proc/1/cgroup")
+        .map(|c| parse_cgroup(&c))
+        .filter(|c| c.iter().any(|c| c.path != "/"))
+        .or_else(|| read_file("/proc/self/cgroup").map(|c| parse_cgroup(&c)))
+        .unwrap_or_default();
+
+    detect(Hints {
+        dockerenv: Path::new("/.dockerenv").exists(),
+        containerenv: Path::new("/run/.containerenv").exists().then(|| {
+            to_hashmap_with_delim(read_file("/run/.containerenv").unwrap_or_default(), '=')
+        }),
+        container_env: environ
+            .get("container")
+            .cloned()
+            .or_else(|| read_file("/run/systemd/container")),
+        kubernetes_env: environ.contains_key("KUBERNETES_SERVICE_HOST")
+            || std::env::var_os("KUBERNETES_SERVICE_HOST").is_some(),
+        cgroups,
+        mountinfo: read_file("/proc/self/mountinfo").unwrap_or_default(),
+        proc_version: read_file("/proc/version").unwrap_or_default(),
+    })
+}
+
+fn detect(hints: Hints) -> ContainerInfo {
+    let from_cgroup = hints.cgroups.iter().find_map(|c| from_cgroup_path(&c.path));
+    let id = from_cgroup
+        .as_ref()
+        .and_then(|(_, id)| id.clone())
+        .or_else(|| id_from_mountinfo(&hints.mountinfo));
+
+    let runtime =
+        if hints.kubernetes_env || matches!(from_cgroup, Some((ContainerRuntime::Kubernetes, _))) {
+            ContainerRuntime::Kubernetes
+        } else if let Some(ref env) = hints.containerenv {
+            match env.get("engine") {
+                Some(engine) if !engine.starts_with("podman") => {
+                    ContainerRuntime::Other(engine.to_string())
+                }
+                _ => ContainerRuntime::Podman,
+            }
+        } else if hints.dockerenv {
+            ContainerRuntime::Docker
+        } else if let Some(env) = hints.container_env.as_deref() {
+            match env {
+                "docker" => ContainerRuntime::Docker,
+                "podman" => ContainerRuntime::Podman,
+                "lxc" | "lxc-libvirt" => ContainerRuntime::Lxc,
+                "systemd-nspawn" => ContainerRuntime::SystemdNspawn,
+                "wsl" => ContainerRuntime::Wsl,
+                other => ContainerRuntime::Other(other.to_string()),
+            }
+        } else if let Some((runtime, _)) = from_cgroup {
+            runtime
+        } else if hints.proc_version.to_lowercase().contains("microsoft") {
+            ContainerRuntime::Wsl
+        } else {
+            ContainerRuntime::None
+        };
+
+    let containerenv = |key: &str| {
+        hints
+            .containerenv
+            .as_ref()
+            .and_then(|e| e.get(key))
+            .filter(|v| !v.is_empty())
+            .cloned()
+    };
+
+    if runtime == ContainerRuntime::None {
+        return ContainerInfo::default();
+    }
+    ContainerInfo {
+        id: containerenv("id").or(id),
+        image: containerenv("image"),
+        runtime,
+    }
+}
+
+fn from_cgroup_path(path: &str) -> Option<(ContainerRuntime, Option<String>)> {
+    //! Identify the runtime and container ID from a cgroup path like
+    //! `/docker/<id>`, `/system.slice/docker-<id>.scope`,
+    //! `/kubepods.slice/.../cri-containerd-<id>.scope` or `/lxc.payload.<name>`
+    let last = path.rsplit('/').next().unwrap_or_default();
+    let unit = last.trim_end_matches(".scope");
+    let hex_id = |s: &str| {
+        if s.len() >= 12 && s.chars().all(|c| c.is_ascii_hexdigit()) {
+            Some(s.to_string())
+        } else {
+            None
+        }
+    };
+
+    if path.contains("kubepods") {
+        let id = ["cri-containerd-", "crio-", "docker-"]
+            .iter()
+            .find_map(|p| unit.strip_prefix(p))
+            .or(Some(unit))
+            .and_then(hex_id);
+        Some((ContainerRuntime::Kubernetes, id))
+    } else if let Some(id) = unit.strip_prefix("docker-") {
+        Some((ContainerRuntime::Docker, hex_id(id)))
+    } else if path.starts_with("/docker/") {
+        Some((ContainerRuntime::Docker, hex_id(last)))
+    } else if let Some(id) = unit.strip_prefix("libpod-") {
+        Some((ContainerRuntime::Podman, hex_id(id)))
+    } else if let Some(id) = unit.strip_prefix("crio-") {
+        Some((ContainerRuntime::CriO, hex_id(id)))
+    } else if let Some(id) = unit.strip_prefix("cri-containerd-") {
+        Some((ContainerRuntime::Containerd, hex_id(id)))
+    } else if let Some(name) = path
+        .strip_prefix("/lxc.payload.")
+        .or_else(|| path.strip_prefix("/lxc/"))
+    {
+        let name = name.split('/').next().unwrap_or_default();
+        Some((ContainerRuntime::Lxc, Some(name.to_string())))
+    } else {
+        // systemd escapes `-` in unit names
+        path.strip_prefix("/machine.slice/machine-")
+            .and_then(|p| p.split('/').next())
+            .and_then(|p| p.strip_suffix(".scope"))
+            .map(|name| {
+                (
+                    ContainerRuntime::SystemdNspawn,
+                    Some(name.replace("\\x2d", "-")),
+                )
+            })
+    }
+}
+
+fn id_from_mountinfo(mountinfo: &str) -> Option<String> {
+    //! With cgroup v2 namespaces the cgroup path is just `/`, but the
+    //! runtime bind mounts files like `/etc/hostname` from a per container
+    //! directory, eg: `/var/lib/docker/containers/<id>/hostname`.
+    mountinfo
+        .lines()
+        .filter_map(|l| l.split_whitespace().nth(3))
+        .find_map(|root| {
+            let mut parts = root.split('/');
+            parts.find(|p| *p == "containers" || *p == "overlay-containers")?;
+            parts
+                .next()
+                .filter(|id| id.len() == 64 && id.chars().all(|c| c.is_ascii_hexdigit()))
+                .map(Into::into)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "4f1e9a6f0cdbf7e6c2b6b0a1e2d3c4b5a6978877665544332211009988776655";
+
+    #[test]
+    fn test_detect_docker_cgroup_v1() {
+        let info = detect(Hints {
+            dockerenv: true,
+            cgroups: parse_cgroup(&format!("12:memory:/docker/{}\n0::/", ID)),
+            ..Default::default()
+        });
+        assert_eq!(info.runtime, ContainerRuntime::Docker);
+        assert_eq!(info.id.as_deref(), Some(ID));
+        assert!(info.is_container());
+    }
+
+    #[test]
+    fn test_detect_docker_cgroup_v2() {
+        let mountinfo = format!(
+            "652 640 254:1 /var/lib/docker/containers/{}/hostname /etc/hostname rw,relatime - ext4 /dev/vda1 rw",
+            ID
+        );
+        let info = detect(Hints {
+            dockerenv: true,
+            cgroups: parse_cgroup("0::/"),
+            mountinfo,
+            ..Default::default()
+        });
+        assert_eq!(info.runtime, ContainerRuntime::Docker);
+        assert_eq!(info.id.as_deref(), Some(ID));
+    }
+
+    #[test]
+    fn test_detect_podman() {
+        let env = to_hashmap_with_delim(
+            format!(
+                "engine=\"podman-4.9.3\"\nname=\"web\"\nid=\"{}\"\nimage=\"docker.io/library/nginx:latest\"\n",
+                ID
+            ),
+            '=',
+        );
+        let info = detect(Hints {
+            containerenv: Some(env),
+            ..Default::default()
+        });
+        assert_eq!(info.runtime, ContainerRuntime::Podman);
+        assert_eq!(info.id.as_deref(), Some(ID));
+        assert_eq!(
+            info.image.as_deref(),
+            Some("docker.io/library/nginx:latest")
+        );
+    }
+
+    #[test]
+    fn test_detect_other_runtimes() {
+        let k8s = detect(Hints {
+            cgroups: parse_cgroup(&format!(
+                "0::/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod1.slice/cri-containerd-{}.scope",
+                ID
+            )),
+            ..Default::default()
+        });
+        assert_eq!(k8s.runtime, ContainerRuntime::Kubernetes);
+        assert_eq!(k8s.id.as_deref(), Some(ID));
+
+        let nspawn = detect(Hints {
+            container_env: Some("systemd-nspawn".to_string()),
+            cgroups: parse_cgroup("0::/machine.slice/machine-my\\x2dbox.scope/payload"),
+            ..Default::default()
+        });
+        assert_eq!(nspawn.runtime, ContainerRuntime::SystemdNspawn);
+        assert_eq!(nspawn.id.as_deref(), Some("my-box"));
+
+        let lxc = detect(Hints {
+            cgroups: parse_cgroup("0::/lxc.payload.web01/init.scope"),
+            ..Default::default()
+        });
+        assert_eq!(lxc.runtime, ContainerRuntime::Lxc);
+        assert_eq!(lxc.id.as_deref(), Some("web01"));
+
+        let wsl = detect(Hints {
+            proc_version: "Linux version 5.15.90.1-microsoft-standard-WSL2".to_string(),
+            ..Default::default()
+        });
+        assert_eq!(wsl.runtime, ContainerRuntime::Wsl);
+
+        let host = detect(Hints {
+            cgroups: parse_cgroup("0::/user.slice/user-1000.slice/session-2.scope"),
+            ..Default::default()
+        });
+        assert_eq!(host.runtime, ContainerRuntime::None);
+        assert!(host.id.is_none());
+    }
+}
@@ -0,0 +1,157 @@
+use crate::common::utils::read_file;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+const SYSFS_CPU: &str = "/sys/devices/system/cpu";
+
+/// CPU frequency scaling information of the system
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CpuFrequencyInfo {
+    /// Global boost/turbo state. `None` when the driver does not expose it.
+    pub boost: Option<bool>,
+    pub cpus: Vec<CpuFrequency>,
+}
+
+/// Frequency scaling state of a single logical CPU.
+/// All frequencies are in kHz, as reported by the kernel.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CpuFrequency {
+    pub cpu: u32,
+    pub current_khz: Option<u64>,
+    /// Lower limit currently enforced by the governor
+    pub min_khz: Option<u64>,
+    /// Upper limit currently enforced by the governor
+    pub max_khz: Option<u64>,
+    /// Guaranteed (non-turbo) frequency
+    pub base_khz: Option<u64>,
+    /// Highest frequency the hardware supports, including turbo
+    pub boost_khz: Option<u64>,
+    pub scaling_driver: Option<String>,
+    pub governor: Option<String>,
+    pub available_governors: Vec<String>,
+    pub energy_performance_preference: Option<String>,
+}
+
+impl CpuFrequencyInfo {
+    pub fn governors(&self) -> Vec<String> {
+        //! Distinct governors in use across all CPUs
+        let mut governors: Vec<String> = self
+            .cpus
+            .iter()
+            .filter_map(|c| c.governor.clone())
+            .collect();
+        governors.sort();
+        governors.dedup();
+        governors
+    }
+}
+
+pub fn cpu_frequency() -> CpuFrequencyInfo {
+    //! Get the per-CPU frequency and scaling governor information
+    //! from `/sys/devices/system/cpu/cpu*/cpufreq`.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let freq = systeminfo::cpu_frequency();
+    //! println!("{:#?}", freq);
+    //! ```
+    from_sysfs(Path::new(SYSFS_CPU))
+}
+
+fn from_sysfs(root: &Path) -> CpuFrequencyInfo {
+    let mut cpus: Vec<CpuFrequency> = fs::read_dir(root)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    let name = e.file_name().into_string().ok()?;
+                    let id = name.strip_prefix("cpu")?.parse::<u32>().ok()?;
+                    let cpufreq = e.path().join("cpufreq");
+                    if cpufreq.is_dir() {
+                        Some(cpu_from_dir(id, &cpufreq))
+                    } else {
+                        None
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    cpus.sort_by_key(|c| c.cpu);
+
+    CpuFrequencyInfo {
+        boost: boost_state(root),
+        cpus,
+    }
+}
+
+fn cpu_from_dir(cpu: u32, dir: &Path) -> CpuFrequency {
+    let read = |name: &str| read_file(dir.join(name));
+    let read_khz = |name: &str| read(name).and_then(|v| v.parse::<u64>().ok());
+
+    CpuFrequency {
+        cpu,
+        current_khz: read_khz("scaling_cur_freq").or_else(|| read_khz("cpuinfo_cur_freq")),
+        min_khz: read_khz("scaling_min_freq"),
+        max_khz: read_khz("scaling_max_freq"),
+        base_khz: read_khz("base_frequency").or_else(|| read_khz("amd_pstate_nominal_freq")),
+        boost_khz: read_khz("cpuinfo_max_freq").or_else(|| read_khz("amd_pstate_max_freq")),
+        scaling_driver: read("scaling_driver"),
+        governor: read("scaling_governor"),
+        available_governors: read("scaling_available_governors")
+            .map(|g| g.split_whitespace().map(Into::into).collect())
+            .unwrap_or_default(),
+        energy_performance_preference: read("energy_performance_preference"),
+    }
+}
+
+fn boost_state(root: &Path) -> Option<bool> {
+    //! `cpufreq/boost` is used by acpi-cpufreq and amd-pstate,
+    //! intel_pstate exposes the inverted `no_turbo` knob instead.
+    if let Some(boost) = read_file(root.join("cpufreq/boost")) {
+        return Some(boost == "1");
+    }
+    read_file(root.join("intel_pstate/no_turbo")).map(|no_turbo| no_turbo == "0")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixture::Fixture;
+
+    #[test]
+    fn test_cpufreq_from_sysfs() {
+        let fixture = Fixture::new("cpufreq");
+        let root = fixture.root();
+        let cpu0 = root.join("cpu0/cpufreq");
+        fixture.write(cpu0.join("scaling_cur_freq"), "2800000\n");
+        fixture.write(cpu0.join("scaling_min_freq"), "400000\n");
+        fixture.write(cpu0.join("scaling_max_freq"), "4700000\n");
+        fixture.write(cpu0.join("base_frequency"), "2800000\n");
+        fixture.write(cpu0.join("cpuinfo_max_freq"), "4700000\n");
+        fixture.write(cpu0.join("scaling_driver"), "intel_pstate\n");
+        fixture.write(cpu0.join("scaling_governor"), "powersave\n");
+        fixture.write(
+            cpu0.join("scaling_available_governors"),
+            "performance powersave\n",
+        );
+        fixture.write(
+            cpu0.join("energy_performance_preference"),
+            "balance_power\n",
+        );
+        fixture.write(root.join("cpu1/cpufreq/scaling_governor"), "performance\n");
+        fixture.write(root.join("intel_pstate/no_turbo"), "1\n");
+        fixture.dir(root.join("cpuidle"));
+
+        let info = from_sysfs(root);
+
+        assert_eq!(info.boost, Some(false));
+        assert_eq!(info.cpus.len(), 2);
+        let cpu0 = &info.cpus[0];
+        assert_eq!(cpu0.current_khz, Some(2_800_000));
+        assert_eq!(cpu0.base_khz, Some(2_800_000));
+        assert_eq!(cpu0.boost_khz, Some(4_700_000));
+        assert_eq!(cpu0.scaling_driver.as_deref(), Some("intel_pstate"));
+        assert_eq!(cpu0.available_governors, vec!["performance", "powersave"]);
+        assert_eq!(info.governors(), vec!["performance", "powersave"]);
+    }
+}
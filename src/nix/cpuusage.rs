@@ -0,0 +1,321 @@
+use crate::common::utils::read_file;
+use serde::{Deserialize, Serialize};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+const PROC_STAT: &str = "/proc/stat";
+
+/// Cumulative CPU time counters from `/proc/stat`, in clock ticks (USER_HZ).
+/// `user` and `nice` already include `guest` and `guest_nice`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CpuTimes {
+    /// `N` of the `cpuN` line, `None` for the aggregate `cpu` line
+    pub cpu: Option<u32>,
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+    pub guest: u64,
+    pub guest_nice: u64,
+}
+
+/// A single reading of `/proc/stat`
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CpuStat {
+    pub total: CpuTimes,
+    pub cpus: Vec<CpuTimes>,
+    pub context_switches: u64,
+    pub interrupts: u64,
+}
+
+/// CPU time spent in each state over a sampling interval, in percent
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct CpuPercent {
+    /// CPU number, `None` for the total
+    pub cpu: Option<u32>,
+    pub user: f64,
+    pub nice: f64,
+    pub system: f64,
+    pub idle: f64,
+    pub iowait: f64,
+    pub irq: f64,
+    pub softirq: f64,
+    pub steal: f64,
+    pub guest: f64,
+}
+
+/// CPU utilization computed between two `/proc/stat` readings
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct CpuUsage {
+    pub total: CpuPercent,
+    pub cpus: Vec<CpuPercent>,
+    pub context_switches_per_sec: f64,
+    pub interrupts_per_sec: f64,
+    pub interval: Duration,
+}
+
+/// Iterator producing a [`CpuUsage`] sample every `interval`
+pub struct CpuUsageIter {
+    interval: Duration,
+    previous: Option<(CpuStat, Instant)>,
+}
+
+impl CpuTimes {
+    fn total(&self) -> u64 {
+        // guest time is already accounted in user/nice
+        self.user
+            + self.nice
+            + self.system
+            + self.idle
+            + self.iowait
+            + self.irq
+            + self.softirq
+            + self.steal
+    }
+
+    fn delta(&self, prev: &Self) -> Self {
+        Self {
+            cpu: self.cpu,
+            user: self.user.saturating_sub(prev.user),
+            nice: self.nice.saturating_sub(prev.nice),
+            system: self.system.saturating_sub(prev.system),
+            idle: self.idle.saturating_sub(prev.idle),
+            iowait: self.iowait.saturating_sub(prev.iowait),
+            irq: self.irq.saturating_sub(prev.irq),
+            softirq: self.softirq.saturating_sub(prev.softirq),
+            steal: self.steal.saturating_sub(prev.steal),
+            guest: self.guest.saturating_sub(prev.guest),
+            guest_nice: self.guest_nice.saturating_sub(prev.guest_nice),
+        }
+    }
+}
+
+impl CpuPercent {
+    fn between(prev: &CpuTimes, cur: &CpuTimes) -> Self {
+        let d = cur.delta(prev);
+        let total = d.total();
+        if total == 0 {
+            return Self {
+                cpu: cur.cpu,
+                ..Default::default()
+            };
+        }
+        let pct = |v: u64| v as f64 * 100. / total as f64;
+        Self {
+            cpu: cur.cpu,
+            user: pct(d.user),
+            nice: pct(d.nice),
+            system: pct(d.system),
+            idle: pct(d.idle),
+            iowait: pct(d.iowait),
+            irq: pct(d.irq),
+            softirq: pct(d.softirq),
+            steal: pct(d.steal),
+            guest: pct(d.guest + d.guest_nice),
+        }
+    }
+
+    /// Percentage of time the CPU was not idle or waiting for I/O
+    pub fn busy(&self) -> f64 {
+        100. - self.idle - self.iowait
+    }
+}
+
+impl CpuStat {
+    pub fn read() -> Option<Self> {
+        //! Take a reading of `/proc/stat`
+        read_file(PROC_STAT).and_then(|s| parse_stat(&s))
+    }
+}
+
+impl CpuUsage {
+    pub fn sample(interval: Duration) -> Option<Self> {
+        //! Sample `/proc/stat` twice, `interval` apart, and
+        //! return the CPU utilization in between.
+        //! ## Example Usage:
+        //! ```ignore
+        //! use std::time::Duration;
+        //! use systeminfo::CpuUsage;
+        //! let usage = CpuUsage::sample(Duration::from_secs(1));
+        //! println!("{:#?}", usage);
+        //! ```
+        Self::iter(interval).next()
+    }
+
+    pub fn iter(interval: Duration) -> CpuUsageIter {
+        //! Returns an iterator that yields a sample every `interval`.
+        //! The iterator ends when `/proc/stat` can no longer be read.
+        //! ## Example Usage:
+        //! ```ignore
+        //! use std::time::Duration;
+        //! use systeminfo::CpuUsage;
+        //! for usage in CpuUsage::iter(Duration::from_secs(1)).take(5) {
+        //!     println!("{:.1}% busy", usage.total.busy());
+        //! }
+        //! ```
+        CpuUsageIter {
+            interval,
+            previous: None,
+        }
+    }
+
+    pub fn between(prev: &CpuStat, cur: &CpuStat, elapsed: Duration) -> Self {
+        //! Compute the utilization between two readings taken `elapsed` apart
+        let secs = elapsed.as_secs_f64();
+        let rate = |prev: u64, cur: u64| {
+            if secs > 0. {
+                cur.saturating_sub(prev) as f64 / secs
+            } else {
+                0.
+            }
+        };
+        Self {
+            total: CpuPercent::between(&prev.total, &cur.total),
+            // CPUs going offline drop their line, pair the rows by CPU number
+            cpus: cur
+                .cpus
+                .iter()
+                .filter_map(|c| {
+                    let p = prev.cpus.iter().find(|p| p.cpu == c.cpu)?;
+                    Some(CpuPercent::between(p, c))
+                })
+                .collect(),
+            context_switches_per_sec: rate(prev.context_switches, cur.context_switches),
+            interrupts_per_sec: rate(prev.interrupts, cur.interrupts),
+            interval: elapsed,
+        }
+    }
+}
+
+impl Iterator for CpuUsageIter {
+    type Item = CpuUsage;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (prev, prev_at) = match self.previous.take() {
+            Some(previous) => previous,
+            None => (CpuStat::read()?, Instant::now()),
+        };
+        thread::sleep(self.interval.saturating_sub(prev_at.elapsed()));
+        let cur = CpuStat::read()?;
+        let now = Instant::now();
+        let usage = CpuUsage::between(&prev, &cur, now - prev_at);
+        self.previous = Some((cur, now));
+        Some(usage)
+    }
+}
+
+fn parse_cpu_times(cpu: Option<u32>, fields: &[&str]) -> CpuTimes {
+    let field = |i: usize| {
+        fields
+            .get(i)
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or_default()
+    };
+    CpuTimes {
+        cpu,
+        user: field(0),
+        nice: field(1),
+        system: field(2),
+        idle: field(3),
+        iowait: field(4),
+        irq: field(5),
+        softirq: field(6),
+        steal: field(7),
+        guest: field(8),
+        guest_nice: field(9),
+    }
+}
+
+pub(crate) fn parse_stat(content: &str) -> Option<CpuStat> {
+    let mut stat = CpuStat::default();
+    let mut found = false;
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let key = match fields.next() {
+            Some(key) => key,
+            None => continue,
+        };
+        let values: Vec<&str> = fields.collect();
+        match key {
+            "cpu" => {
+                stat.total = parse_cpu_times(None, &values);
+                found = true;
+            }
+            k if k.starts_with("cpu") => {
+                let cpu = k["cpu".len()..].parse().ok();
+                stat.cpus.push(parse_cpu_times(cpu, &values));
+            }
+            "ctxt" => stat.context_switches = values.first()?.parse().ok()?,
+            "intr" => stat.interrupts = values.first()?.parse().ok()?,
+            _ => {}
+        }
+    }
+    found.then_some(stat)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STAT_1: &str = "cpu  100 0 50 800 50 0 0 0 0 0
+cpu0 50 0 25 400 25 0 0 0 0 0
+cpu1 50 0 25 400 25 0 0 0 0 0
+intr 1000 0 0
+ctxt 5000
+btime 1610859829
+processes 100
+";
+
+    const STAT_2: &str = "cpu  200 0 100 1600 100 0 0 0 0 0
+cpu0 150 0 50 700 100 0 0 0 0 0
+cpu1 50 0 50 900 0 0 0 0 0 0
+intr 3000 0 0
+ctxt 9000
+btime 1610859829
+processes 120
+";
+
+    #[test]
+    fn test_parse_stat() {
+        let stat = parse_stat(STAT_1).unwrap();
+        assert_eq!(stat.total.user, 100);
+        assert_eq!(stat.total.idle, 800);
+        assert_eq!(stat.cpus.len(), 2);
+        assert_eq!(stat.context_switches, 5000);
+        assert_eq!(stat.interrupts, 1000);
+        assert!(parse_stat("").is_none());
+    }
+
+    #[test]
+    fn test_cpu_usage_between() {
+        let prev = parse_stat(STAT_1).unwrap();
+        let cur = parse_stat(STAT_2).unwrap();
+        let usage = CpuUsage::between(&prev, &cur, Duration::from_secs(2));
+        assert_eq!(usage.total.user, 10.);
+        assert_eq!(usage.total.idle, 80.);
+        assert_eq!(usage.total.busy(), 15.);
+        assert_eq!(usage.cpus[0].user, 20.);
+        assert_eq!((usage.cpus[1].idle * 100.).round() / 100., 95.24);
+        assert_eq!(usage.context_switches_per_sec, 2000.);
+        assert_eq!(usage.interrupts_per_sec, 1000.);
+    }
+
+    #[test]
+    fn test_cpu_offline() {
+        let prev = parse_stat(STAT_1).unwrap();
+        // cpu0 went offline
+        let cur = parse_stat("cpu  200 0 100 1600 100 0 0 0 0 0\ncpu1 50 0 50 900 0 0 0 0 0 0\n")
+            .unwrap();
+        assert_eq!(cur.cpus[0].cpu, Some(1));
+        let usage = CpuUsage::between(&prev, &cur, Duration::from_secs(2));
+        assert_eq!(usage.cpus.len(), 1);
+        assert_eq!(usage.cpus[0].cpu, Some(1));
+        assert_eq!((usage.cpus[0].idle * 100.).round() / 100., 95.24);
+    }
+}
@@ -0,0 +1,232 @@
+use crate::common::utils::read_file;
+use serde::{Deserialize, Serialize};
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// `/proc/diskstats` always counts in 512 byte sectors,
+/// regardless of the device's logical block size.
+const SECTOR_SIZE: u64 = 512;
+
+/// Cumulative I/O counters of a block device from `/proc/diskstats`.
+/// Times are in milliseconds.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DiskStat {
+    pub major: u32,
+    pub minor: u32,
+    pub name: String,
+    pub reads: u64,
+    pub reads_merged: u64,
+    pub sectors_read: u64,
+    pub read_time: u64,
+    pub writes: u64,
+    pub writes_merged: u64,
+    pub sectors_written: u64,
+    pub write_time: u64,
+    pub in_flight: u64,
+    pub io_time: u64,
+    pub weighted_io_time: u64,
+    /// Discard fields, available since Linux 4.18
+    pub discards: u64,
+    pub discards_merged: u64,
+    pub sectors_discarded: u64,
+    pub discard_time: u64,
+    /// Flush fields, available since Linux 5.5
+    pub flushes: u64,
+    pub flush_time: u64,
+}
+
+/// A single reading of `/proc/diskstats`
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct DiskStats {
+    pub disks: Vec<DiskStat>,
+}
+
+/// Extended I/O statistics of a block device between two readings,
+/// like the ones reported by `iostat -x`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct DiskIo {
+    pub name: String,
+    pub reads_per_sec: f64,
+    pub writes_per_sec: f64,
+    pub discards_per_sec: f64,
+    pub flushes_per_sec: f64,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    pub discard_bytes_per_sec: f64,
+    pub reads_merged_per_sec: f64,
+    pub writes_merged_per_sec: f64,
+    /// Average time, in milliseconds, for read requests to be served
+    pub read_await: f64,
+    /// Average time, in milliseconds, for write requests to be served
+    pub write_await: f64,
+    pub discard_await: f64,
+    pub flush_await: f64,
+    /// Average queue length of the requests issued to the device
+    pub avg_queue_size: f64,
+    /// Percentage of elapsed time during which the device was busy
+    pub utilization: f64,
+    pub in_flight: u64,
+}
+
+impl DiskStats {
+    pub fn read() -> Option<Self> {
+        //! Take a reading of `/proc/diskstats`
+        read_file("/proc/diskstats").map(|s| parse_diskstats(&s))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&DiskStat> {
+        //! Counters of the device with the given name (eg: `sda`, `nvme0n1`)
+        self.disks.iter().find(|d| d.name == name)
+    }
+
+    pub fn sample(interval: Duration) -> Option<Vec<DiskIo>> {
+        //! Read `/proc/diskstats` twice, `interval` apart, and
+        //! return the per device I/O statistics in between.
+        //! ## Example Usage:
+        //! ```ignore
+        //! use std::time::Duration;
+        //! use systeminfo::DiskStats;
+        //! for disk in DiskStats::sample(Duration::from_secs(1)).unwrap_or_default() {
+        //!     println!("{}: {:.1}% util, {:.2} ms await", disk.name, disk.utilization, disk.read_await);
+        //! }
+        //! ```
+        let prev = Self::read()?;
+        let prev_at = Instant::now();
+        thread::sleep(interval);
+        let cur = Self::read()?;
+        Some(Self::between(&prev, &cur, prev_at.elapsed()))
+    }
+
+    pub fn between(prev: &Self, cur: &Self, elapsed: Duration) -> Vec<DiskIo> {
+        //! Compute the I/O statistics between two readings taken `elapsed` apart.
+        //! Devices missing from either reading are skipped.
+        cur.disks
+            .iter()
+            .filter_map(|c| Some(DiskIo::between(prev.get(&c.name)?, c, elapsed)))
+            .collect()
+    }
+}
+
+impl DiskIo {
+    fn between(prev: &DiskStat, cur: &DiskStat, elapsed: Duration) -> Self {
+        let secs = elapsed.as_secs_f64();
+        let ms = secs * 1000.;
+        let delta = |p: u64, c: u64| c.saturating_sub(p) as f64;
+        let rate = |p: u64, c: u64| if secs > 0. { delta(p, c) / secs } else { 0. };
+        let bytes_rate = |p: u64, c: u64| rate(p, c) * SECTOR_SIZE as f64;
+        let await_ms = |(pt, ct): (u64, u64), (pn, cn): (u64, u64)| {
+            let ios = delta(pn, cn);
+            if ios > 0. {
+                delta(pt, ct) / ios
+            } else {
+                0.
+            }
+        };
+        let per_ms = |p: u64, c: u64| if ms > 0. { delta(p, c) / ms } else { 0. };
+
+        Self {
+            name: cur.name.clone(),
+            reads_per_sec: rate(prev.reads, cur.reads),
+            writes_per_sec: rate(prev.writes, cur.writes),
+            discards_per_sec: rate(prev.discards, cur.discards),
+            flushes_per_sec: rate(prev.flushes, cur.flushes),
+            read_bytes_per_sec: bytes_rate(prev.sectors_read, cur.sectors_read),
+            write_bytes_per_sec: bytes_rate(prev.sectors_written, cur.sectors_written),
+            discard_bytes_per_sec: bytes_rate(prev.sectors_discarded, cur.sectors_discarded),
+            reads_merged_per_sec: rate(prev.reads_merged, cur.reads_merged),
+            writes_merged_per_sec: rate(prev.writes_merged, cur.writes_merged),
+            read_await: await_ms((prev.read_time, cur.read_time), (prev.reads, cur.reads)),
+            write_await: await_ms((prev.write_time, cur.write_time), (prev.writes, cur.writes)),
+            discard_await: await_ms(
+                (prev.discard_time, cur.discard_time),
+                (prev.discards, cur.discards),
+            ),
+            flush_await: await_ms(
+                (prev.flush_time, cur.flush_time),
+                (prev.flushes, cur.flushes),
+            ),
+            avg_queue_size: per_ms(prev.weighted_io_time, cur.weighted_io_time),
+            utilization: (per_ms(prev.io_time, cur.io_time) * 100.).min(100.),
+            in_flight: cur.in_flight,
+        }
+    }
+}
+
+fn parse_diskstat(line: &str) -> Option<DiskStat> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    if fields.len() < 14 {
+        return None;
+    }
+    let field = |i: usize| {
+        fields
+            .get(i)
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or_default()
+    };
+    Some(DiskStat {
+        major: fields[0].parse().ok()?,
+        minor: fields[1].parse().ok()?,
+        name: fields[2].to_string(),
+        reads: field(3),
+        reads_merged: field(4),
+        sectors_read: field(5),
+        read_time: field(6),
+        writes: field(7),
+        writes_merged: field(8),
+        sectors_written: field(9),
+        write_time: field(10),
+        in_flight: field(11),
+        io_time: field(12),
+        weighted_io_time: field(13),
+        discards: field(14),
+        discards_merged: field(15),
+        sectors_discarded: field(16),
+        discard_time: field(17),
+        flushes: field(18),
+        flush_time: field(19),
+    })
+}
+
+fn parse_diskstats(content: &str) -> DiskStats {
+    DiskStats {
+        disks: content.lines().filter_map(parse_diskstat).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_diskstats() {
+        let stats = parse_diskstats(
+            " 259  0 nvme0n1 1000 10 80000 500 2000 20 160000 4000 1 3000 4500 5 0 800 2 100 50\n   8  0 sda 1 2 3 4 5 6 7 8 9 10 11\n",
+        );
+        assert_eq!(stats.disks.len(), 2);
+        let nvme = stats.get("nvme0n1").unwrap();
+        assert_eq!(nvme.major, 259);
+        assert_eq!(nvme.sectors_written, 160000);
+        assert_eq!(nvme.discards, 5);
+        assert_eq!(nvme.flush_time, 50);
+        assert_eq!(stats.get("sda").unwrap().flushes, 0);
+    }
+
+    #[test]
+    fn test_disk_io_between() {
+        let prev = parse_diskstats("8 0 sda 1000 0 8000 1000 1000 0 8000 2000 0 1000 3000\n");
+        let cur = parse_diskstats("8 0 sda 1200 10 10048 1400 1100 0 8800 3000 2 1500 4000\n");
+        let io = DiskStats::between(&prev, &cur, Duration::from_secs(1));
+        assert_eq!(io.len(), 1);
+        let sda = &io[0];
+        assert_eq!(sda.reads_per_sec, 200.);
+        assert_eq!(sda.read_bytes_per_sec, 2048. * 512.);
+        assert_eq!(sda.reads_merged_per_sec, 10.);
+        assert_eq!(sda.read_await, 2.);
+        assert_eq!(sda.write_await, 10.);
+        assert_eq!(sda.avg_queue_size, 1.);
+        assert_eq!(sda.utilization, 50.);
+        assert_eq!(sda.in_flight, 2);
+    }
+}
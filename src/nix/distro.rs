@@ -0,0 +1,221 @@
+use super::os_release::os_release;
+use crate::common::Version;
+use core::fmt::{Display, Formatter, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Distributions without numbered releases
+const ROLLING: [&str; 8] = [
+    "arch",
+    "artix",
+    "endeavouros",
+    "gentoo",
+    "kali",
+    "manjaro",
+    "opensuse-tumbleweed",
+    "void",
+];
+
+/// Family of related distributions, sharing package format and tooling
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub enum DistroFamily {
+    /// Debian, Ubuntu, Mint, Raspberry Pi OS, ...
+    Debian,
+    /// RHEL, Fedora, CentOS, Rocky, AlmaLinux, Amazon Linux, Oracle Linux, ...
+    Rhel,
+    /// SLES, openSUSE Leap and Tumbleweed
+    Suse,
+    /// Arch, Manjaro, EndeavourOS, ...
+    Arch,
+    Alpine,
+    Gentoo,
+    NixOs,
+    Slackware,
+    Void,
+    /// A distribution not belonging to a known family, with its `ID`
+    Other(String),
+    #[default]
+    Unknown,
+}
+
+impl DistroFamily {
+    fn from_id(id: &str) -> Option<Self> {
+        Some(match id {
+            "debian" | "ubuntu" | "linuxmint" | "raspbian" | "pop" | "elementary" | "kali"
+            | "devuan" | "zorin" => Self::Debian,
+            "rhel" | "fedora" | "centos" | "rocky" | "almalinux" | "amzn" | "ol" | "scientific"
+            | "cloudlinux" | "mariner" | "azurelinux" => Self::Rhel,
+            "suse" | "sles" | "sled" | "opensuse" | "opensuse-leap" | "opensuse-tumbleweed" => {
+                Self::Suse
+            }
+            "arch" | "manjaro" | "endeavouros" | "artix" | "archarm" => Self::Arch,
+            "alpine" | "postmarketos" => Self::Alpine,
+            "gentoo" => Self::Gentoo,
+            "nixos" => Self::NixOs,
+            "slackware" => Self::Slackware,
+            "void" => Self::Void,
+            _ => return None,
+        })
+    }
+}
+
+impl Display for DistroFamily {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match *self {
+            Self::Debian => write!(f, "debian"),
+            Self::Rhel => write!(f, "rhel"),
+            Self::Suse => write!(f, "suse"),
+            Self::Arch => write!(f, "arch"),
+            Self::Alpine => write!(f, "alpine"),
+            Self::Gentoo => write!(f, "gentoo"),
+            Self::NixOs => write!(f, "nixos"),
+            Self::Slackware => write!(f, "slackware"),
+            Self::Void => write!(f, "void"),
+            Self::Other(ref id) => write!(f, "{}", id),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Linux distribution, as described by `os-release(5)`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Distro {
+    /// eg: `ubuntu`
+    pub id: String,
+    /// Distributions this one is derived from, closest first, eg: `["ubuntu", "debian"]`
+    pub id_like: Vec<String>,
+    pub family: DistroFamily,
+    /// eg: `Ubuntu`
+    pub name: String,
+    /// eg: `Ubuntu 22.04.3 LTS`
+    pub pretty_name: Option<String>,
+    /// eg: `22.04.3 LTS (Jammy Jellyfish)`
+    pub version: Option<String>,
+    pub version_id: Version,
+    /// eg: `jammy`
+    pub codename: Option<String>,
+    /// eg: `Server Edition`
+    pub variant: Option<String>,
+    pub variant_id: Option<String>,
+    pub build_id: Option<String>,
+    pub home_url: Option<String>,
+    pub documentation_url: Option<String>,
+    pub support_url: Option<String>,
+    pub bug_report_url: Option<String>,
+}
+
+impl Distro {
+    pub fn from_fields(fields: &HashMap<String, String>) -> Self {
+        //! Build from the `KEY=value` fields of an os-release file
+        let get = |key: &str| fields.get(key).filter(|v| !v.is_empty()).cloned();
+        let id = get("ID").unwrap_or_else(|| "linux".to_string());
+        let id_like: Vec<String> = get("ID_LIKE")
+            .map(|l| l.split_whitespace().map(Into::into).collect())
+            .unwrap_or_default();
+        let family = std::iter::once(&id)
+            .chain(id_like.iter())
+            .find_map(|id| DistroFamily::from_id(id))
+            .unwrap_or_else(|| match id.as_str() {
+                "linux" => DistroFamily::Unknown,
+                id => DistroFamily::Other(id.to_string()),
+            });
+        let build_id = get("BUILD_ID");
+        let version_id = match get("VERSION_ID") {
+            // Tumbleweed uses the snapshot date as VERSION_ID
+            v if ROLLING.contains(&id.as_str()) || build_id.as_deref() == Some("rolling") => {
+                Version::Rolling(v.or_else(|| build_id.clone().filter(|b| b != "rolling")))
+            }
+            Some(v) => Version::from_string(v),
+            None => Version::Unknown,
+        };
+        Self {
+            name: get("NAME").unwrap_or_else(|| "Linux".to_string()),
+            pretty_name: get("PRETTY_NAME"),
+            version: get("VERSION"),
+            version_id,
+            codename: get("VERSION_CODENAME").or_else(|| get("UBUNTU_CODENAME")),
+            variant: get("VARIANT"),
+            variant_id: get("VARIANT_ID"),
+            build_id,
+            home_url: get("HOME_URL"),
+            documentation_url: get("DOCUMENTATION_URL"),
+            support_url: get("SUPPORT_URL"),
+            bug_report_url: get("BUG_REPORT_URL"),
+            id,
+            id_like,
+            family,
+        }
+    }
+
+    pub fn is_like(&self, id: &str) -> bool {
+        //! The distribution is `id` or derived from it
+        self.id == id || self.id_like.iter().any(|l| l == id)
+    }
+}
+
+pub fn distro() -> Option<Distro> {
+    //! Get the Linux distribution of the running system.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let distro = systeminfo::distro().unwrap();
+    //! println!("{} {} ({})", distro.id, distro.version_id, distro.family);
+    //! ```
+    os_release().map(|fields| Distro::from_fields(&fields))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::os_release::parse_os_release as fields;
+    use super::*;
+
+    #[test]
+    fn test_ubuntu() {
+        let distro = Distro::from_fields(&fields(
+            "NAME=\"Ubuntu\"\nVERSION_ID=\"22.04\"\nVERSION=\"22.04.3 LTS (Jammy Jellyfish)\"\n\
+             ID=ubuntu\nID_LIKE=debian\nVERSION_CODENAME=jammy\n\
+             SUPPORT_URL=\"https://help.ubuntu.com/\"\n",
+        ));
+        assert_eq!(distro.family, DistroFamily::Debian);
+        assert_eq!(distro.version_id, Version::Semantic(22, 4, 0));
+        assert_eq!(distro.codename.as_deref(), Some("jammy"));
+        assert!(distro.is_like("debian"));
+        assert_eq!(
+            distro.support_url.as_deref(),
+            Some("https://help.ubuntu.com/")
+        );
+    }
+
+    #[test]
+    fn test_family_from_id_like() {
+        let distro = Distro::from_fields(&fields(
+            "NAME=\"Rocky Linux\"\nID=\"rocky-derivative\"\nID_LIKE=\"rhel centos fedora\"\n\
+             VERSION_ID=\"9.3\"\nVARIANT=\"Server\"\n",
+        ));
+        assert_eq!(distro.family, DistroFamily::Rhel);
+        assert_eq!(distro.id_like, vec!["rhel", "centos", "fedora"]);
+        assert_eq!(distro.version_id, Version::Semantic(9, 3, 0));
+        assert_eq!(distro.variant.as_deref(), Some("Server"));
+    }
+
+    #[test]
+    fn test_rolling() {
+        let arch = Distro::from_fields(&fields("NAME=\"Arch Linux\"\nID=arch\nBUILD_ID=rolling\n"));
+        assert_eq!(arch.family, DistroFamily::Arch);
+        assert_eq!(arch.version_id, Version::Rolling(None));
+
+        let tumbleweed = Distro::from_fields(&fields(
+            "NAME=\"openSUSE Tumbleweed\"\nID=\"opensuse-tumbleweed\"\n\
+             ID_LIKE=\"opensuse suse\"\nVERSION_ID=\"20240101\"\n",
+        ));
+        assert_eq!(tumbleweed.family, DistroFamily::Suse);
+        assert_eq!(
+            tumbleweed.version_id,
+            Version::Rolling(Some("20240101".into()))
+        );
+
+        let unknown = Distro::from_fields(&fields("ID=mylinux\n"));
+        assert_eq!(unknown.family, DistroFamily::Other("mylinux".into()));
+        assert_eq!(unknown.version_id, Version::Unknown);
+    }
+}
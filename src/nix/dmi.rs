@@ -0,0 +1,44 @@
+use crate::common::utils::read_file;
+use std::path::Path;
+
+const SYSFS_DMI: &str = "/sys/class/dmi/id";
+
+/// SMBIOS/DMI strings exported by the kernel in `/sys/class/dmi/id`.
+/// Unlike `dmidecode`, reading most of these does not require root.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Dmi {
+    pub sys_vendor: Option<String>,
+    pub product_name: Option<String>,
+    pub product_version: Option<String>,
+    /// Only readable by root
+    pub product_serial: Option<String>,
+    /// Only readable by root
+    pub product_uuid: Option<String>,
+    pub board_vendor: Option<String>,
+    pub board_asset_tag: Option<String>,
+    pub bios_vendor: Option<String>,
+    pub bios_version: Option<String>,
+    pub chassis_asset_tag: Option<String>,
+}
+
+impl Dmi {
+    pub fn read() -> Self {
+        Self::from_dir(Path::new(SYSFS_DMI))
+    }
+
+    pub fn from_dir(dir: &Path) -> Self {
+        let read = |name: &str| read_file(dir.join(name));
+        Self {
+            sys_vendor: read("sys_vendor"),
+            product_name: read("product_name"),
+            product_version: read("product_version"),
+            product_serial: read("product_serial"),
+            product_uuid: read("product_uuid"),
+            board_vendor: read("board_vendor"),
+            board_asset_tag: read("board_asset_tag"),
+            bios_vendor: read("bios_vendor"),
+            bios_version: read("bios_version"),
+            chassis_asset_tag: read("chassis_asset_tag"),
+        }
+    }
+}
@@ -0,0 +1,404 @@
+use crate::common::utils::read_file;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+const SYSFS_EFI: &str = "/sys/firmware/efi";
+/// Vendor GUID of the UEFI global variables (`EFI_GLOBAL_VARIABLE`)
+const EFI_GLOBAL_VARIABLE: &str = "8be4df61-93ca-11d2-aa0d-00e098032b8c";
+/// `LOAD_OPTION_ACTIVE` attribute of an `EFI_LOAD_OPTION`
+const LOAD_OPTION_ACTIVE: u32 = 0x0000_0001;
+
+/// Firmware interface the system booted with
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum FirmwareMode {
+    #[default]
+    Bios,
+    Uefi,
+}
+
+/// A `Boot####` load option
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct EfiBootEntry {
+    /// The `####` of `Boot####`
+    pub number: u16,
+    pub description: String,
+    pub active: bool,
+    /// Device path in the UEFI text representation,
+    /// eg: `HD(1,GPT,<guid>,0x800,0x100000)/\EFI\debian\shimx64.efi`
+    pub device_path: String,
+}
+
+/// Firmware mode, Secure Boot state and EFI boot configuration
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FirmwareInfo {
+    pub mode: FirmwareMode,
+    pub secure_boot: Option<bool>,
+    /// Firmware is in setup mode: no Platform Key is enrolled
+    /// and Secure Boot is not enforced
+    pub setup_mode: Option<bool>,
+    /// Bitness of the UEFI firmware (32 or 64)
+    pub platform_size: Option<u8>,
+    pub boot_current: Option<u16>,
+    pub boot_order: Vec<u16>,
+    pub boot_entries: Vec<EfiBootEntry>,
+}
+
+pub fn firmware() -> FirmwareInfo {
+    //! Get the firmware mode (UEFI or legacy BIOS), the Secure Boot state
+    //! and the EFI boot entries from `/sys/firmware/efi`.
+    //! Reading the EFI variables does not require root.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let fw = systeminfo::firmware();
+    //! println!("{:?} secure boot: {:?}", fw.mode, fw.secure_boot);
+    //! for entry in fw.boot_entries {
+    //!     println!("Boot{:04X} {} {}", entry.number, entry.description, entry.device_path);
+    //! }
+    //! ```
+    from_sysfs(Path::new(SYSFS_EFI))
+}
+
+fn from_sysfs(efi: &Path) -> FirmwareInfo {
+    if !efi.is_dir() {
+        return FirmwareInfo::default();
+    }
+    let efivars = efi.join("efivars");
+    let var = |name: &str| efi_variable(&efivars, name);
+
+    let mut boot_entries: Vec<EfiBootEntry> = fs::read_dir(&efivars)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    let name = e.file_name().into_string().ok()?;
+                    let number = name
+                        .strip_prefix("Boot")?
+                        .strip_suffix(EFI_GLOBAL_VARIABLE)?
+                        .strip_suffix('-')
+                        .filter(|n| n.len() == 4)?;
+                    let number = u16::from_str_radix(number, 16).ok()?;
+                    parse_load_option(number, &var(&format!("Boot{:04X}", number))?)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    boot_entries.sort_by_key(|e| e.number);
+
+    FirmwareInfo {
+        mode: FirmwareMode::Uefi,
+        secure_boot: var("SecureBoot").and_then(|v| v.first().map(|&b| b == 1)),
+        setup_mode: var("SetupMode").and_then(|v| v.first().map(|&b| b == 1)),
+        platform_size: read_file(efi.join("fw_platform_size")).and_then(|s| s.parse().ok()),
+        boot_current: var("BootCurrent").and_then(|v| u16_at(&v, 0)),
+        boot_order: var("BootOrder")
+            .map(|v| {
+                v.chunks_exact(2)
+                    .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        boot_entries,
+    }
+}
+
+fn efi_variable(efivars: &Path, name: &str) -> Option<Vec<u8>> {
+    //! Read a global EFI variable, skipping the 4 byte attributes header
+    let content = fs::read(efivars.join(format!("{}-{}", name, EFI_GLOBAL_VARIABLE))).ok()?;
+    content.get(4..).map(|data| data.to_vec())
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn u64_at(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        data.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+fn ucs2(data: &[u8]) -> (String, usize) {
+    //! Decode a NUL terminated UCS-2 string,
+    //! returns the string and the number of bytes consumed
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|c| u16::from_le_bytes([c[0], c[1]]))
+        .take_while(|&u| u != 0)
+        .collect();
+    let consumed = ((units.len() + 1) * 2).min(data.len());
+    (String::from_utf16_lossy(&units), consumed)
+}
+
+fn guid(data: &[u8]) -> Option<String> {
+    //! Format an EFI GUID, the first three fields are little endian
+    let d = data.get(..16)?;
+    Some(format!(
+        "{:08x}-{:04x}-{:04x}-{:02x}{:02x}-{}",
+        u32_at(d, 0)?,
+        u16_at(d, 4)?,
+        u16_at(d, 6)?,
+        d[8],
+        d[9],
+        d[10..]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>()
+    ))
+}
+
+fn parse_load_option(number: u16, data: &[u8]) -> Option<EfiBootEntry> {
+    // EFI_LOAD_OPTION: u32 Attributes, u16 FilePathListLength,
+    // CHAR16 Description[], EFI_DEVICE_PATH FilePathList[], u8 OptionalData[]
+    let attributes = u32_at(data, 0)?;
+    let path_len = u16_at(data, 4)? as usize;
+    let (description, consumed) = ucs2(data.get(6..)?);
+    let start = 6 + consumed;
+    let path = data.get(start..start + path_len).unwrap_or_default();
+    Some(EfiBootEntry {
+        number,
+        description,
+        active: attributes & LOAD_OPTION_ACTIVE != 0,
+        device_path: device_path_to_text(path),
+    })
+}
+
+fn device_path_to_text(mut data: &[u8]) -> String {
+    let mut nodes = Vec::new();
+    while data.len() >= 4 {
+        let (kind, subtype) = (data[0], data[1]);
+        let len = u16_at(data, 2).unwrap_or_default() as usize;
+        if len < 4 || len > data.len() {
+            break;
+        }
+        let node = &data[4..len];
+        match (kind, subtype) {
+            // End of hardware device path
+            (0x7f, 0xff) => break,
+            (0x7f, _) => nodes.push(",".to_string()),
+            _ => nodes.push(node_to_text(kind, subtype, node)),
+        }
+        data = &data[len..];
+    }
+    nodes.join("/").replace("/,/", ",")
+}
+
+fn node_to_text(kind: u8, subtype: u8, node: &[u8]) -> String {
+    //! Text representation of a device path node, following the
+    //! UEFI specification "Device Path to Text" conventions
+    let text = match (kind, subtype) {
+        // Hardware
+        (0x01, 0x01) => node
+            .get(..2)
+            .map(|n| format!("Pci(0x{:x},0x{:x})", n[1], n[0])),
+        (0x01, 0x04) => guid(node).map(|g| format!("VenHw({})", g)),
+        // ACPI
+        (0x02, 0x01) => match (u32_at(node, 0), u32_at(node, 4)) {
+            (Some(0x0a03_41d0), Some(uid)) => Some(format!("PciRoot(0x{:x})", uid)),
+            (Some(0x0a08_41d0), Some(uid)) => Some(format!("PcieRoot(0x{:x})", uid)),
+            (Some(hid), Some(uid)) => Some(format!("Acpi(0x{:08x},0x{:x})", hid, uid)),
+            _ => None,
+        },
+        // Messaging
+        (0x03, 0x02) => Some(format!(
+            "Scsi(0x{:x},0x{:x})",
+            u16_at(node, 0).unwrap_or_default(),
+            u16_at(node, 2).unwrap_or_default()
+        )),
+        (0x03, 0x05) => node
+            .get(..2)
+            .map(|n| format!("USB(0x{:x},0x{:x})", n[0], n[1])),
+        (0x03, 0x0b) => node.get(..6).map(|mac| {
+            format!(
+                "MAC({})",
+                mac.iter().map(|b| format!("{:02x}", b)).collect::<String>()
+            )
+        }),
+        (0x03, 0x0c) => node
+            .get(4..8)
+            .map(|ip| format!("IPv4({}.{}.{}.{})", ip[0], ip[1], ip[2], ip[3])),
+        (0x03, 0x12) => Some(format!(
+            "Sata(0x{:x},0x{:x},0x{:x})",
+            u16_at(node, 0).unwrap_or_default(),
+            u16_at(node, 2).unwrap_or_default(),
+            u16_at(node, 4).unwrap_or_default()
+        )),
+        (0x03, 0x17) => u32_at(node, 0).map(|nsid| {
+            let eui = node
+                .get(4..12)
+                .unwrap_or_default()
+                .iter()
+                .map(|b| format!("{:02X}", b))
+                .collect::<Vec<String>>()
+                .join("-");
+            format!("NVMe(0x{:x},{})", nsid, eui)
+        }),
+        (0x03, 0x18) => Some(format!("Uri({})", String::from_utf8_lossy(node))),
+        // Media
+        (0x04, 0x01) => {
+            let partition = u32_at(node, 0);
+            let start = u64_at(node, 4);
+            let size = u64_at(node, 12);
+            let format = node.get(36).copied();
+            let signature = match node.get(37) {
+                Some(0x02) => guid(node.get(20..36).unwrap_or_default()),
+                Some(0x01) => u32_at(node, 20).map(|s| format!("0x{:08x}", s)),
+                _ => None,
+            };
+            match (partition, start, size) {
+                (Some(partition), Some(start), Some(size)) => Some(format!(
+                    "HD({},{},{},0x{:x},0x{:x})",
+                    partition,
+                    match format {
+                        Some(0x01) => "MBR",
+                        Some(0x02) => "GPT",
+                        _ => "Unknown",
+                    },
+                    signature.unwrap_or_default(),
+                    start,
+                    size
+                )),
+                _ => None,
+            }
+        }
+        (0x04, 0x02) => u32_at(node, 0).map(|entry| format!("CDROM(0x{:x})", entry)),
+        (0x04, 0x04) => Some(ucs2(node).0),
+        (0x04, 0x06) => guid(node).map(|g| format!("FvFile({})", g)),
+        (0x04, 0x07) => guid(node).map(|g| format!("Fv({})", g)),
+        // BIOS Boot Specification
+        (0x05, 0x01) => u16_at(node, 0).map(|device_type| {
+            let description = node.get(4..).unwrap_or_default();
+            let end = description
+                .iter()
+                .position(|&b| b == 0)
+                .unwrap_or(description.len());
+            format!(
+                "BBS(0x{:x},{})",
+                device_type,
+                String::from_utf8_lossy(&description[..end])
+            )
+        }),
+        _ => None,
+    };
+    text.unwrap_or_else(|| {
+        format!(
+            "Path({},{},{})",
+            kind,
+            subtype,
+            node.iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixture::Fixture;
+
+    fn utf16(s: &str) -> Vec<u8> {
+        s.encode_utf16()
+            .chain(Some(0))
+            .flat_map(|u| u.to_le_bytes())
+            .collect()
+    }
+
+    fn node(kind: u8, subtype: u8, data: &[u8]) -> Vec<u8> {
+        let mut node = vec![kind, subtype];
+        node.extend(((data.len() + 4) as u16).to_le_bytes());
+        node.extend(data);
+        node
+    }
+
+    fn load_option() -> Vec<u8> {
+        let mut hd = Vec::new();
+        hd.extend(1u32.to_le_bytes());
+        hd.extend(2048u64.to_le_bytes());
+        hd.extend(1048576u64.to_le_bytes());
+        hd.extend([
+            0x78, 0x56, 0x34, 0x12, 0x34, 0x12, 0x78, 0x56, 0x9a, 0xbc, 0xde, 0xf0, 0x12, 0x34,
+            0x56, 0x78,
+        ]);
+        hd.extend([0x02, 0x02]);
+        let mut path = node(0x04, 0x01, &hd);
+        path.extend(node(0x04, 0x04, &utf16("\\EFI\\debian\\shimx64.efi")));
+        path.extend(node(0x7f, 0xff, &[]));
+
+        let mut option = Vec::new();
+        option.extend(LOAD_OPTION_ACTIVE.to_le_bytes());
+        option.extend((path.len() as u16).to_le_bytes());
+        option.extend(utf16("debian"));
+        option.extend(path);
+        option
+    }
+
+    #[test]
+    fn test_parse_load_option() {
+        let entry = parse_load_option(1, &load_option()).unwrap();
+        assert_eq!(entry.description, "debian");
+        assert!(entry.active);
+        assert_eq!(
+            entry.device_path,
+            "HD(1,GPT,12345678-1234-5678-9abc-def012345678,0x800,0x100000)/\\EFI\\debian\\shimx64.efi"
+        );
+    }
+
+    #[test]
+    fn test_device_path_fallbacks() {
+        let mut path = node(0x02, 0x01, &[0xd0, 0x41, 0x03, 0x0a, 0, 0, 0, 0]);
+        path.extend(node(0x01, 0x01, &[0x00, 0x1f]));
+        path.extend(node(0x03, 0x12, &[0, 0, 0xff, 0xff, 0, 0]));
+        path.extend(node(0x09, 0x01, &[0xab]));
+        path.extend(node(0x7f, 0xff, &[]));
+        assert_eq!(
+            device_path_to_text(&path),
+            "PciRoot(0x0)/Pci(0x1f,0x0)/Sata(0x0,0xffff,0x0)/Path(9,1,ab)"
+        );
+    }
+
+    #[test]
+    fn test_firmware_from_sysfs() {
+        let fixture = Fixture::new("efi");
+        let root = fixture.root();
+        let efivars = root.join("efivars");
+        fixture.dir(&efivars);
+        let efivar = |name: &str, data: &[u8]| {
+            let mut content = vec![0x07, 0, 0, 0];
+            content.extend(data);
+            fixture.write(
+                efivars.join(format!("{}-{}", name, EFI_GLOBAL_VARIABLE)),
+                content,
+            );
+        };
+        efivar("SecureBoot", &[1]);
+        efivar("SetupMode", &[0]);
+        efivar("BootCurrent", &[0x01, 0x00]);
+        efivar("BootOrder", &[0x01, 0x00, 0x00, 0x00]);
+        efivar("Boot0001", &load_option());
+        fixture.write(root.join("fw_platform_size"), "64\n");
+
+        let fw = from_sysfs(root);
+
+        assert_eq!(fw.mode, FirmwareMode::Uefi);
+        assert_eq!(fw.secure_boot, Some(true));
+        assert_eq!(fw.setup_mode, Some(false));
+        assert_eq!(fw.platform_size, Some(64));
+        assert_eq!(fw.boot_current, Some(1));
+        assert_eq!(fw.boot_order, vec![1, 0]);
+        assert_eq!(fw.boot_entries.len(), 1);
+        assert_eq!(fw.boot_entries[0].number, 1);
+
+        let bios = from_sysfs(Path::new("/nonexistent"));
+        assert_eq!(bios.mode, FirmwareMode::Bios);
+        assert!(bios.secure_boot.is_none());
+    }
+}
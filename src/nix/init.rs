@@ -0,0 +1,369 @@
+use crate::common::utils::read_file;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+/// System unit directories, highest priority first
+const UNIT_DIRS: [&str; 5] = [
+    "etc/systemd/system",
+    "run/systemd/system",
+    "usr/local/lib/systemd/system",
+    "usr/lib/systemd/system",
+    "lib/systemd/system",
+];
+
+/// Unit file suffixes, see `systemd.unit(5)`. Scopes and devices are only
+/// created at runtime and have no unit files.
+const UNIT_TYPES: [&str; 9] = [
+    "service",
+    "socket",
+    "target",
+    "timer",
+    "mount",
+    "automount",
+    "swap",
+    "path",
+    "slice",
+];
+
+/// Process running as PID 1
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub enum InitSystem {
+    Systemd,
+    OpenRc,
+    Runit,
+    S6,
+    /// SysV `init`
+    SysVinit,
+    DumbInit,
+    /// tini, also used by `docker run --init`
+    Tini,
+    /// Another process, with its name (eg: a container entrypoint)
+    Other(String),
+    #[default]
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct InitInfo {
+    pub init: InitSystem,
+    /// `/proc/1/comm`
+    pub comm: Option<String>,
+    /// Target of `/proc/1/exe`, only readable by root
+    pub exe: Option<String>,
+}
+
+/// Enablement state of a unit file, as shown by `systemctl list-unit-files`
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UnitFileState {
+    Enabled,
+    /// Enabled in `/run`, until the next reboot
+    EnabledRuntime,
+    Disabled,
+    /// No `[Install]` section, started as a dependency of other units
+    Static,
+    /// Only pulls other units in with `Also=`
+    Indirect,
+    /// Symlinked to `/dev/null`
+    Masked,
+    /// Symlink to another unit under a different name
+    Alias,
+    /// Symlink to a unit file outside the unit directories
+    Linked,
+}
+
+/// An installed systemd unit file
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct UnitFile {
+    /// eg: `ssh.service`
+    pub name: String,
+    pub path: String,
+    /// eg: `service`, `timer`
+    pub unit_type: String,
+    pub state: UnitFileState,
+}
+
+pub fn init_system() -> InitInfo {
+    //! Get the init system running as PID 1.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! println!("{:?}", systeminfo::init_system().init);
+    //! ```
+    init_from_root(Path::new("/"))
+}
+
+fn init_from_root(root: &Path) -> InitInfo {
+    let comm = read_file(root.join("proc/1/comm"));
+    let exe = fs::read_link(root.join("proc/1/exe"))
+        .ok()
+        .map(|e| e.display().to_string());
+    let exe_name = exe
+        .as_deref()
+        .and_then(|e| e.trim_end_matches(" (deleted)").rsplit('/').next());
+
+    let init = match exe_name.or(comm.as_deref()) {
+        Some("systemd") => InitSystem::Systemd,
+        Some("openrc-init") => InitSystem::OpenRc,
+        Some("runit" | "runit-init") => InitSystem::Runit,
+        Some("s6-svscan") => InitSystem::S6,
+        Some("dumb-init") => InitSystem::DumbInit,
+        Some("tini" | "docker-init") => InitSystem::Tini,
+        // OpenRC usually runs under SysV init
+        Some("init") if root.join("run/openrc").is_dir() => InitSystem::OpenRc,
+        // systemd without /proc/1/exe access, see sd_booted(3)
+        Some("init") if root.join("run/systemd/system").is_dir() => InitSystem::Systemd,
+        Some("init") => InitSystem::SysVinit,
+        Some(other) => InitSystem::Other(other.to_string()),
+        None => InitSystem::Unknown,
+    };
+    InitInfo { init, comm, exe }
+}
+
+pub fn unit_files() -> Vec<UnitFile> {
+    //! Get the installed systemd unit files and their enablement state from
+    //! the unit directories, without D-Bus or a running systemd.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! for unit in systeminfo::unit_files() {
+    //!     println!("{} {:?}", unit.name, unit.state);
+    //! }
+    //! ```
+    units_from_root(Path::new("/"))
+}
+
+fn wanted_units(dir: &Path) -> HashSet<String> {
+    //! Units pulled in by the `.wants`, `.requires` and `.upholds` symlinks of a directory
+    let mut units = HashSet::new();
+    for entry in fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+    {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if [".wants", ".requires", ".upholds"]
+            .iter()
+            .any(|s| name.ends_with(s))
+        {
+            for unit in fs::read_dir(entry.path())
+                .into_iter()
+                .flatten()
+                .filter_map(|e| e.ok())
+            {
+                let unit = unit.file_name().to_string_lossy().into_owned();
+                // An instance enables its template, eg: getty@tty1.service
+                if let Some((template, rest)) = unit.split_once('@') {
+                    if let Some((_, suffix)) = rest.rsplit_once('.') {
+                        units.insert(format!("{}@.{}", template, suffix));
+                    }
+                }
+                units.insert(unit);
+            }
+        }
+    }
+    units
+}
+
+fn install_section(content: &str) -> BTreeMap<String, Vec<String>> {
+    let mut install = BTreeMap::new();
+    let mut in_install = false;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_install = line == "[Install]";
+        } else if in_install && !line.starts_with(['#', ';']) {
+            if let Some((key, value)) = line.split_once('=') {
+                install
+                    .entry(key.trim().to_string())
+                    .or_insert_with(Vec::new)
+                    .extend(value.split_whitespace().map(String::from));
+            }
+        }
+    }
+    install
+}
+
+fn link_target(root: &Path, link: &Path) -> Option<PathBuf> {
+    //! Absolute target of a symlink below `root`, as seen from inside `root`
+    let target = fs::read_link(link).ok()?;
+    if target.is_absolute() {
+        return Some(target);
+    }
+    let mut resolved = PathBuf::from("/");
+    let relative = link.parent()?.strip_prefix(root).ok()?.join(target);
+    for component in relative.components() {
+        match component {
+            Component::Normal(c) => resolved.push(c),
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            _ => {}
+        }
+    }
+    Some(resolved)
+}
+
+fn units_from_root(root: &Path) -> Vec<UnitFile> {
+    let dirs: Vec<PathBuf> = UNIT_DIRS.iter().map(|d| root.join(d)).collect();
+    let (etc, run) = (&dirs[0], &dirs[1]);
+    let enabled = wanted_units(etc);
+    let enabled_runtime = wanted_units(run);
+
+    // The first directory defining a unit wins
+    let mut files: BTreeMap<String, PathBuf> = BTreeMap::new();
+    for dir in &dirs {
+        for entry in fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|e| e.ok())
+        {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let is_unit = name
+                .rsplit_once('.')
+                .is_some_and(|(_, t)| UNIT_TYPES.contains(&t));
+            if is_unit && !entry.path().is_dir() {
+                files.entry(name).or_insert_with(|| entry.path());
+            }
+        }
+    }
+
+    files
+        .into_iter()
+        .map(|(name, path)| {
+            let link = link_target(root, &path);
+            let state = match link {
+                Some(ref target) if target == Path::new("/dev/null") => UnitFileState::Masked,
+                Some(ref target)
+                    if target
+                        .file_name()
+                        .is_some_and(|t| t.to_string_lossy() != name) =>
+                {
+                    UnitFileState::Alias
+                }
+                Some(ref target)
+                    if !UNIT_DIRS
+                        .iter()
+                        .any(|d| target.starts_with(Path::new("/").join(d))) =>
+                {
+                    UnitFileState::Linked
+                }
+                _ => {
+                    let install = fs::read_to_string(&path)
+                        .map(|c| install_section(&c))
+                        .unwrap_or_default();
+                    let aliased = |dir: &Path| {
+                        install
+                            .get("Alias")
+                            .is_some_and(|a| a.iter().any(|a| dir.join(a).exists()))
+                    };
+                    if enabled.contains(&name) || aliased(etc) {
+                        UnitFileState::Enabled
+                    } else if enabled_runtime.contains(&name) || aliased(run) {
+                        UnitFileState::EnabledRuntime
+                    } else if ["WantedBy", "RequiredBy", "UpheldBy", "Alias"]
+                        .iter()
+                        .any(|k| install.contains_key(*k))
+                    {
+                        UnitFileState::Disabled
+                    } else if install.contains_key("Also") {
+                        UnitFileState::Indirect
+                    } else {
+                        UnitFileState::Static
+                    }
+                }
+            };
+            UnitFile {
+                unit_type: name
+                    .rsplit_once('.')
+                    .map(|(_, t)| t.to_string())
+                    .unwrap_or_default(),
+                path: path.display().to_string(),
+                name,
+                state,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixture::Fixture;
+
+    #[test]
+    fn test_unit_files() {
+        let fixture = Fixture::new("units");
+        let root = fixture.root();
+        let (etc, lib) = (
+            root.join("etc/systemd/system"),
+            root.join("usr/lib/systemd/system"),
+        );
+        fixture.dir(etc.join("multi-user.target.wants"));
+        fixture.dir(etc.join("getty.target.wants"));
+        fixture.dir(&lib);
+        let install = "[Unit]\nDescription=x\n\n[Install]\nWantedBy=multi-user.target\n";
+        fixture.write(lib.join("ssh.service"), install);
+        fixture.write(lib.join("cups.service"), install);
+        fixture.write(
+            lib.join("getty@.service"),
+            "[Install]\nWantedBy=getty.target\n",
+        );
+        fixture.write(
+            lib.join("systemd-journald.service"),
+            "[Service]\nType=notify\n",
+        );
+        fixture.write(lib.join("cups.path"), "[Install]\nAlso=cups.service\n");
+        fixture.write(lib.join("timesyncd.service"), install);
+        fixture.symlink(
+            lib.join("ssh.service"),
+            etc.join("multi-user.target.wants/ssh.service"),
+        );
+        fixture.symlink(
+            lib.join("getty@.service"),
+            etc.join("getty.target.wants/getty@tty1.service"),
+        );
+        fixture.symlink("/dev/null", etc.join("cups.service"));
+        fixture.symlink(
+            lib.join("timesyncd.service"),
+            etc.join("dbus-org.timesync1.service"),
+        );
+        fixture.write(lib.join("rsync.service"), install);
+        fixture.symlink(
+            "../../../usr/lib/systemd/system/rsync.service",
+            etc.join("rsync.service"),
+        );
+        fixture.symlink("/opt/app/app.service", etc.join("app.service"));
+
+        let units = units_from_root(root);
+
+        let state = |name: &str| units.iter().find(|u| u.name == name).unwrap().state;
+        assert_eq!(state("ssh.service"), UnitFileState::Enabled);
+        assert_eq!(state("getty@.service"), UnitFileState::Enabled);
+        assert_eq!(state("cups.service"), UnitFileState::Masked);
+        assert_eq!(state("cups.path"), UnitFileState::Indirect);
+        assert_eq!(state("systemd-journald.service"), UnitFileState::Static);
+        assert_eq!(state("timesyncd.service"), UnitFileState::Disabled);
+        assert_eq!(state("dbus-org.timesync1.service"), UnitFileState::Alias);
+        assert_eq!(state("rsync.service"), UnitFileState::Disabled);
+        assert_eq!(state("app.service"), UnitFileState::Linked);
+        assert_eq!(units.len(), 9);
+    }
+
+    #[test]
+    fn test_init_from_root() {
+        let fixture = Fixture::new("init");
+        let root = fixture.root();
+        fixture.dir(root.join("proc/1"));
+        fixture.dir(root.join("run/openrc"));
+        fixture.write(root.join("proc/1/comm"), "init\n");
+        let openrc = init_from_root(root);
+        fixture.write(root.join("proc/1/comm"), "tini\n");
+        let tini = init_from_root(root);
+
+        assert_eq!(openrc.init, InitSystem::OpenRc);
+        assert_eq!(tini.init, InitSystem::Tini);
+    }
+}
@@ -0,0 +1,173 @@
+use super::kernel::kernel;
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, io::Read, path::Path};
+
+const PROC_CONFIG: &str = "/proc/config.gz";
+
+/// Value of a `CONFIG_*` kernel build option
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ConfigValue {
+    /// Built into the kernel (`y`)
+    Yes,
+    /// Built as a loadable module (`m`)
+    Module,
+    /// Disabled (`n` or `# CONFIG_... is not set`)
+    No,
+    Int(i64),
+    /// Hexadecimal value, eg: `CONFIG_PHYSICAL_START=0x1000000`
+    Hex(u64),
+    String(String),
+}
+
+/// Build configuration of the running kernel
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct KernelConfig {
+    /// File the configuration was read from
+    pub source: String,
+    pub options: BTreeMap<String, ConfigValue>,
+}
+
+impl KernelConfig {
+    pub fn parse(config: &str) -> Self {
+        let options = config
+            .lines()
+            .filter_map(|l| {
+                let l = l.trim();
+                if let Some(name) = l
+                    .strip_prefix("# ")
+                    .and_then(|l| l.strip_suffix(" is not set"))
+                {
+                    return Some((name.to_string(), ConfigValue::No));
+                }
+                if l.starts_with('#') {
+                    return None;
+                }
+                let (name, value) = l.split_once('=')?;
+                let value = match value {
+                    "y" => ConfigValue::Yes,
+                    "m" => ConfigValue::Module,
+                    "n" => ConfigValue::No,
+                    v if v.starts_with('"') => {
+                        ConfigValue::String(v.trim_matches('"').replace("\\\"", "\""))
+                    }
+                    v => match v.strip_prefix("0x") {
+                        Some(hex) => u64::from_str_radix(hex, 16)
+                            .map(ConfigValue::Hex)
+                            .unwrap_or_else(|_| ConfigValue::String(v.to_string())),
+                        None => v
+                            .parse()
+                            .map(ConfigValue::Int)
+                            .unwrap_or_else(|_| ConfigValue::String(v.to_string())),
+                    },
+                };
+                Some((name.to_string(), value))
+            })
+            .collect();
+        Self {
+            options,
+            ..Default::default()
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&ConfigValue> {
+        //! Get an option, with or without the `CONFIG_` prefix
+        if name.starts_with("CONFIG_") {
+            self.options.get(name)
+        } else {
+            self.options.get(&format!("CONFIG_{}", name))
+        }
+    }
+
+    pub fn is_enabled(&self, name: &str) -> bool {
+        //! The option is built in or available as a module
+        matches!(self.get(name), Some(ConfigValue::Yes | ConfigValue::Module))
+    }
+
+    pub fn is_builtin(&self, name: &str) -> bool {
+        self.get(name) == Some(&ConfigValue::Yes)
+    }
+
+    pub fn is_module(&self, name: &str) -> bool {
+        self.get(name) == Some(&ConfigValue::Module)
+    }
+}
+
+pub fn kernel_config() -> Option<KernelConfig> {
+    //! Get the build configuration of the running kernel from `/proc/config.gz`
+    //! (`CONFIG_IKCONFIG_PROC`), or from `/boot/config-$(uname -r)`.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let config = systeminfo::kernel_config().unwrap();
+    //! assert!(config.is_enabled("CONFIG_BPF_SYSCALL"));
+    //! ```
+    let boot = kernel().map(|k| format!("/boot/config-{}", k.release));
+    std::iter::once(PROC_CONFIG.to_string())
+        .chain(boot)
+        .find_map(|path| {
+            let config = read_config(Path::new(&path))?;
+            Some(KernelConfig {
+                source: path,
+                ..KernelConfig::parse(&config)
+            })
+        })
+}
+
+fn read_config(path: &Path) -> Option<String> {
+    let content = fs::read(path).ok()?;
+    // gzip magic
+    if content.starts_with(&[0x1f, 0x8b]) {
+        let mut config = String::new();
+        GzDecoder::new(content.as_slice())
+            .read_to_string(&mut config)
+            .ok()?;
+        Some(config)
+    } else {
+        String::from_utf8(content).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixture::Fixture;
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::Write;
+
+    const CONFIG: &str = "#\n# Automatically generated file; DO NOT EDIT.\n#\n\
+        CONFIG_BPF_SYSCALL=y\nCONFIG_BTRFS_FS=m\n# CONFIG_KASAN is not set\n\
+        CONFIG_NR_CPUS=8192\nCONFIG_PHYSICAL_START=0x1000000\n\
+        CONFIG_LOCALVERSION=\"-generic\"\nCONFIG_FRAME_WARN=-1\n";
+
+    #[test]
+    fn test_parse_config() {
+        let config = KernelConfig::parse(CONFIG);
+        assert!(config.is_enabled("CONFIG_BPF_SYSCALL"));
+        assert!(config.is_builtin("BPF_SYSCALL"));
+        assert!(config.is_module("CONFIG_BTRFS_FS"));
+        assert!(!config.is_enabled("CONFIG_KASAN"));
+        assert_eq!(config.get("CONFIG_KASAN"), Some(&ConfigValue::No));
+        assert_eq!(config.get("NR_CPUS"), Some(&ConfigValue::Int(8192)));
+        assert_eq!(config.get("FRAME_WARN"), Some(&ConfigValue::Int(-1)));
+        assert_eq!(
+            config.get("PHYSICAL_START"),
+            Some(&ConfigValue::Hex(0x1000000))
+        );
+        assert_eq!(
+            config.get("LOCALVERSION"),
+            Some(&ConfigValue::String("-generic".into()))
+        );
+        assert_eq!(config.get("CONFIG_MISSING"), None);
+    }
+
+    #[test]
+    fn test_read_gzip_config() {
+        let fixture = Fixture::new("config");
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(CONFIG.as_bytes()).unwrap();
+        let path = fixture.write("config.gz", encoder.finish().unwrap());
+        let config = read_config(&path);
+        assert_eq!(config.as_deref(), Some(CONFIG));
+    }
+}
@@ -0,0 +1,146 @@
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, ffi::CStr, os::raw::c_char};
+
+/// Running kernel, as reported by `uname(2)`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct KernelInfo {
+    /// eg: `Linux`
+    pub sysname: String,
+    /// eg: `5.11.0-1008-raspi`
+    pub release: String,
+    /// Build version, eg: `#8-Ubuntu SMP PREEMPT Thu Feb 11 ...`
+    pub version: String,
+    /// eg: `aarch64`
+    pub machine: String,
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    /// Distribution ABI number or build suffix, eg: `1008` or `362.el9.x86_64`
+    pub abi: Option<String>,
+    /// Kernel flavor, eg: `raspi`, `generic`, `aws` or `cloud-amd64`
+    pub flavor: Option<String>,
+}
+
+impl KernelInfo {
+    pub fn from_release(release: &str) -> Self {
+        //! Parse a kernel release string like `5.11.0-1008-raspi`
+        //! into its version, ABI and flavor
+        let (numbers, local) = match release.find(['-', '+']) {
+            Some(i) => (&release[..i], Some(release[i + 1..].to_string())),
+            None => (release, None),
+        };
+        let mut numbers = numbers.split('.').map(|n| {
+            // eg: `4.19.0rc3`
+            let digits: String = n.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse().unwrap_or_default()
+        });
+        let (abi, flavor) = match local.filter(|l| !l.is_empty()) {
+            Some(local) => match local.split_once('-') {
+                // Arch: `6.6.7-arch1-1` has a package release and no flavor
+                Some((abi, flavor)) if !flavor.chars().all(|c| c.is_ascii_digit()) => {
+                    (Some(abi.to_string()), Some(flavor.to_string()))
+                }
+                _ => (Some(local), None),
+            },
+            None => (None, None),
+        };
+        Self {
+            release: release.to_string(),
+            major: numbers.next().unwrap_or_default(),
+            minor: numbers.next().unwrap_or_default(),
+            patch: numbers.next().unwrap_or_default(),
+            abi,
+            flavor,
+            ..Default::default()
+        }
+    }
+
+    pub fn version_tuple(&self) -> (u32, u32, u32) {
+        (self.major, self.minor, self.patch)
+    }
+
+    pub fn is_at_least(&self, major: u32, minor: u32, patch: u32) -> bool {
+        //! ## Example Usage:
+        //! ```ignore
+        //! if systeminfo::kernel().map_or(false, |k| k.is_at_least(5, 6, 0)) {
+        //!     // io_uring with IORING_OP_OPENAT is available
+        //! }
+        //! ```
+        self.version_tuple() >= (major, minor, patch)
+    }
+
+    pub fn cmp_version(&self, other: &KernelInfo) -> Ordering {
+        //! Compare the upstream versions, ignoring the ABI and flavor
+        self.version_tuple().cmp(&other.version_tuple())
+    }
+}
+
+pub fn kernel() -> Option<KernelInfo> {
+    //! Get the running kernel from `uname(2)`.
+    //! Unlike `hostnamectl` this also works inside containers.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let kernel = systeminfo::kernel().unwrap();
+    //! println!("{}.{} {:?}", kernel.major, kernel.minor, kernel.flavor);
+    //! ```
+    let mut uts: libc::utsname = unsafe { std::mem::zeroed() };
+    if unsafe { libc::uname(&mut uts) } != 0 {
+        return None;
+    }
+    let field = |f: &[c_char]| {
+        unsafe { CStr::from_ptr(f.as_ptr()) }
+            .to_string_lossy()
+            .into_owned()
+    };
+    Some(KernelInfo {
+        sysname: field(&uts.sysname),
+        version: field(&uts.version),
+        machine: field(&uts.machine),
+        ..KernelInfo::from_release(&field(&uts.release))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_release() {
+        let k = KernelInfo::from_release("5.11.0-1008-raspi");
+        assert_eq!(k.version_tuple(), (5, 11, 0));
+        assert_eq!(k.abi.as_deref(), Some("1008"));
+        assert_eq!(k.flavor.as_deref(), Some("raspi"));
+
+        let k = KernelInfo::from_release("5.10.0-25-cloud-amd64");
+        assert_eq!(k.flavor.as_deref(), Some("cloud-amd64"));
+
+        let k = KernelInfo::from_release("5.14.0-362.el9.x86_64");
+        assert_eq!(k.abi.as_deref(), Some("362.el9.x86_64"));
+        assert_eq!(k.flavor, None);
+
+        let k = KernelInfo::from_release("6.6.7-arch1-1");
+        assert_eq!(k.abi.as_deref(), Some("arch1-1"));
+        assert_eq!(k.flavor, None);
+
+        let k = KernelInfo::from_release("6.1");
+        assert_eq!(k.version_tuple(), (6, 1, 0));
+        assert_eq!(k.abi, None);
+    }
+
+    #[test]
+    fn test_compare() {
+        let old = KernelInfo::from_release("4.19.0-rc3");
+        let new = KernelInfo::from_release("5.4.0-42-generic");
+        assert_eq!(old.cmp_version(&new), Ordering::Less);
+        assert!(new.is_at_least(5, 4, 0));
+        assert!(!new.is_at_least(5, 4, 1));
+    }
+
+    #[test]
+    fn test_kernel() {
+        let k = kernel().unwrap();
+        assert_eq!(k.sysname, "Linux");
+        assert!(k.major > 0);
+    }
+}
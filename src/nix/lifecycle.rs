@@ -0,0 +1,375 @@
+// Offline end of life table of distribution releases.
+//
+// The bundled dates come from the vendors' lifecycle pages, cross-checked
+// with endoflife.date. The table is refreshed before every release of the
+// crate: add the releases published since the last refresh and adjust
+// announced date changes (eg: extended ESM or LTS terms). Dates not announced
+// yet are estimated from the vendor's release schedule. Releases missing from
+// a published build can be added at runtime with `LifecycleTable::insert`.
+use super::distro::{distro, Distro};
+use crate::{common::Version, consts::SystemOS};
+use chrono::{Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// Bundled lifecycle data: `ID`, `NAME` (only for IDs shared by several
+/// products), release, codename, end of standard support and end of extended
+/// support (ESM, LTS, ELS or LTSS), as `YYYY-MM-DD`
+const RELEASES: &[(&str, &str, &str, &str, &str, &str)] = &[
+    ("ubuntu", "", "16.04", "xenial", "2021-04-30", "2026-04-30"),
+    ("ubuntu", "", "18.04", "bionic", "2023-05-31", "2028-04-30"),
+    ("ubuntu", "", "20.04", "focal", "2025-05-31", "2030-04-30"),
+    ("ubuntu", "", "22.04", "jammy", "2027-06-01", "2032-04-30"),
+    ("ubuntu", "", "24.04", "noble", "2029-05-31", "2034-04-30"),
+    ("ubuntu", "", "24.10", "oracular", "2025-07-10", ""),
+    ("ubuntu", "", "25.04", "plucky", "2026-01-15", ""),
+    ("ubuntu", "", "25.10", "questing", "2026-07-31", ""),
+    (
+        "ubuntu",
+        "",
+        "26.04",
+        "resolute",
+        "2031-05-31",
+        "2036-04-30",
+    ),
+    ("debian", "", "9", "stretch", "2020-07-06", "2022-06-30"),
+    ("debian", "", "10", "buster", "2022-09-10", "2024-06-30"),
+    ("debian", "", "11", "bullseye", "2024-08-14", "2026-08-31"),
+    ("debian", "", "12", "bookworm", "2026-06-10", "2028-06-30"),
+    ("debian", "", "13", "trixie", "2028-08-09", "2030-06-30"),
+    ("rhel", "", "7", "", "2024-06-30", "2028-06-30"),
+    ("rhel", "", "8", "", "2029-05-31", "2032-05-31"),
+    ("rhel", "", "9", "", "2032-05-31", "2035-05-31"),
+    ("rhel", "", "10", "", "2035-05-31", "2038-05-31"),
+    ("centos", "", "7", "", "2024-06-30", ""),
+    // Both report `ID=centos` and `VERSION_ID=8`
+    ("centos", "CentOS Linux", "8", "", "2021-12-31", ""),
+    ("centos", "CentOS Stream", "8", "", "2024-05-31", ""),
+    ("centos", "", "9", "", "2027-05-31", ""),
+    ("centos", "", "10", "", "2030-01-01", ""),
+    ("rocky", "", "8", "", "2029-05-31", ""),
+    ("rocky", "", "9", "", "2032-05-31", ""),
+    ("rocky", "", "10", "", "2035-05-31", ""),
+    ("almalinux", "", "8", "", "2029-03-01", ""),
+    ("almalinux", "", "9", "", "2032-05-31", ""),
+    ("almalinux", "", "10", "", "2035-05-31", ""),
+    ("fedora", "", "39", "", "2024-11-26", ""),
+    ("fedora", "", "40", "", "2025-05-13", ""),
+    ("fedora", "", "41", "", "2025-12-15", ""),
+    ("fedora", "", "42", "", "2026-05-13", ""),
+    ("fedora", "", "43", "", "2026-12-09", ""),
+    ("fedora", "", "44", "", "2027-05-19", ""),
+    ("amzn", "", "2", "", "2026-06-30", ""),
+    ("amzn", "", "2023", "", "2029-06-30", ""),
+    ("sles", "", "12", "", "2024-10-31", "2027-10-31"),
+    ("sles", "", "15", "", "2031-07-31", "2034-07-31"),
+    ("sles", "", "16", "", "2038-11-30", "2041-11-30"),
+    ("opensuse-leap", "", "15.5", "", "2024-12-31", ""),
+    ("opensuse-leap", "", "15.6", "", "2026-04-30", ""),
+    ("opensuse-leap", "", "16.0", "", "2027-10-31", ""),
+    ("alpine", "", "3.18", "", "2025-05-09", ""),
+    ("alpine", "", "3.19", "", "2025-11-01", ""),
+    ("alpine", "", "3.20", "", "2026-04-01", ""),
+    ("alpine", "", "3.21", "", "2026-11-01", ""),
+    ("alpine", "", "3.22", "", "2027-05-01", ""),
+    ("alpine", "", "3.23", "", "2027-11-01", ""),
+    ("alpine", "", "3.24", "", "2028-05-01", ""),
+];
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum SupportStatus {
+    Supported,
+    /// Past standard support, still covered by extended support (ESM, LTS, ELS, ...)
+    ExtendedSupport,
+    EndOfLife,
+    /// The release is not in the lifecycle table
+    #[default]
+    Unknown,
+}
+
+/// Support dates of a distribution release
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Release {
+    /// `os-release` ID, eg: `ubuntu`
+    pub id: String,
+    /// `os-release` NAME the release is limited to, for IDs shared by several
+    /// products, eg: `CentOS Linux` and `CentOS Stream`
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Release, matching the version or its leading components, eg: `22.04` or `9`
+    pub version: String,
+    pub codename: Option<String>,
+    /// End of standard support
+    pub eol: NaiveDate,
+    /// End of extended support, if any
+    pub extended_eol: Option<NaiveDate>,
+}
+
+impl Release {
+    pub fn status_on(&self, date: NaiveDate) -> SupportStatus {
+        if date <= self.eol {
+            SupportStatus::Supported
+        } else if self.extended_eol.is_some_and(|e| date <= e) {
+            SupportStatus::ExtendedSupport
+        } else {
+            SupportStatus::EndOfLife
+        }
+    }
+}
+
+/// Support status of a distribution release
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Lifecycle {
+    pub status: SupportStatus,
+    /// The matching release of the table
+    pub release: Option<Release>,
+    /// Days until the end of the current support phase, negative once EOL
+    pub days_remaining: Option<i64>,
+}
+
+/// Offline table of distribution support dates.
+/// `LifecycleTable::default()` holds the bundled data, callers can add or
+/// replace releases, or deserialize a complete table of their own.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LifecycleTable {
+    pub releases: Vec<Release>,
+}
+
+impl Default for LifecycleTable {
+    fn default() -> Self {
+        let date = |d: &str| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok();
+        let releases = RELEASES
+            .iter()
+            .filter_map(|&(id, name, version, codename, eol, extended)| {
+                Some(Release {
+                    id: id.to_string(),
+                    name: (!name.is_empty()).then(|| name.to_string()),
+                    version: version.to_string(),
+                    codename: (!codename.is_empty()).then(|| codename.to_string()),
+                    eol: date(eol)?,
+                    extended_eol: date(extended),
+                })
+            })
+            .collect();
+        Self { releases }
+    }
+}
+
+impl LifecycleTable {
+    pub fn insert(&mut self, release: Release) {
+        //! Add a release, replacing the entry of the same ID, NAME and version
+        self.releases.retain(|r| {
+            !(r.id == release.id && r.name == release.name && r.version == release.version)
+        });
+        self.releases.push(release);
+    }
+
+    pub fn find(&self, id: &str, name: Option<&str>, version: &Version) -> Option<&Release> {
+        //! Find the release for a distribution ID, NAME and version. The most
+        //! specific release wins, eg: version `9.3` matches release `9`.
+        //! Releases limited to a NAME only match that NAME.
+        self.releases
+            .iter()
+            .filter(|r| r.id == id)
+            .filter(|r| r.name.is_none() || r.name.as_deref() == name)
+            .filter_map(|r| Some((r, matched_components(&r.version, version)?)))
+            .max_by_key(|&(_, components)| components)
+            .map(|(r, _)| r)
+    }
+
+    pub fn lifecycle(&self, id: &str, name: Option<&str>, version: &Version) -> Lifecycle {
+        self.lifecycle_on(id, name, version, Local::now().date_naive())
+    }
+
+    pub fn lifecycle_on(
+        &self,
+        id: &str,
+        name: Option<&str>,
+        version: &Version,
+        date: NaiveDate,
+    ) -> Lifecycle {
+        match self.find(id, name, version) {
+            Some(release) => {
+                let status = release.status_on(date);
+                let end = match status {
+                    SupportStatus::ExtendedSupport => release.extended_eol,
+                    _ if date > release.eol => release.extended_eol.or(Some(release.eol)),
+                    _ => Some(release.eol),
+                };
+                Lifecycle {
+                    status,
+                    days_remaining: end.map(|end| (end - date).num_days()),
+                    release: Some(release.clone()),
+                }
+            }
+            None => Lifecycle::default(),
+        }
+    }
+
+    pub fn for_distro(&self, distro: &Distro) -> Lifecycle {
+        //! ## Example Usage:
+        //! ```ignore
+        //! use systeminfo::{distro, LifecycleTable};
+        //! let lifecycle = LifecycleTable::default().for_distro(&distro().unwrap());
+        //! println!("{:?} ({:?} days left)", lifecycle.status, lifecycle.days_remaining);
+        //! ```
+        self.for_distro_on(distro, Local::now().date_naive())
+    }
+
+    pub fn for_distro_on(&self, distro: &Distro, date: NaiveDate) -> Lifecycle {
+        self.lifecycle_on(&distro.id, Some(&distro.name), &distro.version_id, date)
+    }
+
+    pub fn for_system_os(&self, os: &SystemOS) -> Lifecycle {
+        //! ## Example Usage:
+        //! ```ignore
+        //! use systeminfo::{from_system_os, LifecycleTable};
+        //! let lifecycle = LifecycleTable::default().for_system_os(&from_system_os());
+        //! println!("{:?} ({:?} days left)", lifecycle.status, lifecycle.days_remaining);
+        //! ```
+        self.lifecycle(
+            &os.os_id,
+            Some(&os.os),
+            &Version::from_string(os.version_id.as_str()),
+        )
+    }
+}
+
+fn matched_components(release: &str, version: &Version) -> Option<usize> {
+    //! Number of leading components of `version` matched by `release`
+    match version {
+        Version::Semantic(major, minor, patch) => {
+            let parts: Vec<u64> = release
+                .split('.')
+                .map(|p| p.parse().ok())
+                .collect::<Option<_>>()?;
+            (parts.len() <= 3 && parts.iter().zip([major, minor, patch]).all(|(a, b)| a == b))
+                .then_some(parts.len())
+        }
+        Version::Custom(version) => (version == release
+            || version
+                .strip_prefix(release)
+                .is_some_and(|rest| rest.starts_with('.')))
+        .then(|| release.split('.').count()),
+        // Rolling releases have no end of life
+        Version::Rolling(_) | Version::Unknown => None,
+    }
+}
+
+pub fn distro_lifecycle() -> Lifecycle {
+    //! Get the support status of the running distribution release
+    //! from the bundled lifecycle table.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let lifecycle = systeminfo::distro_lifecycle();
+    //! println!("{:?} until {:?}", lifecycle.status, lifecycle.release.map(|r| r.eol));
+    //! ```
+    distro()
+        .map(|distro| LifecycleTable::default().for_distro(&distro))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn date(d: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap()
+    }
+
+    fn version(v: &str) -> Version {
+        Version::from_string(v)
+    }
+
+    #[test]
+    fn test_bundled_table() {
+        let table = LifecycleTable::default();
+        assert_eq!(table.releases.len(), RELEASES.len());
+        assert_eq!(
+            table.find("rhel", None, &version("9.3")).unwrap().version,
+            "9"
+        );
+        assert_eq!(
+            table
+                .find("alpine", None, &version("3.19.1"))
+                .unwrap()
+                .version,
+            "3.19"
+        );
+        assert!(table.find("alpine", None, &version("3.1")).is_none());
+        assert_eq!(
+            table
+                .find("ubuntu", None, &version("22.04"))
+                .unwrap()
+                .codename
+                .as_deref(),
+            Some("jammy")
+        );
+
+        let jammy = table.lifecycle_on("ubuntu", None, &version("22.04"), date("2026-06-01"));
+        assert_eq!(jammy.status, SupportStatus::Supported);
+        assert_eq!(jammy.days_remaining, Some(365));
+
+        let bionic = table.lifecycle_on("ubuntu", None, &version("18.04"), date("2025-01-01"));
+        assert_eq!(bionic.status, SupportStatus::ExtendedSupport);
+
+        let buster = table.lifecycle_on("debian", None, &version("10"), date("2025-01-01"));
+        assert_eq!(buster.status, SupportStatus::EndOfLife);
+        assert!(buster.days_remaining.unwrap() < 0);
+
+        assert_eq!(
+            table
+                .lifecycle_on("arch", None, &Version::Rolling(None), date("2025-01-01"))
+                .status,
+            SupportStatus::Unknown
+        );
+
+        let centos = |name| {
+            table
+                .lifecycle_on("centos", name, &version("8"), date("2023-01-01"))
+                .status
+        };
+        assert_eq!(centos(Some("CentOS Linux")), SupportStatus::EndOfLife);
+        assert_eq!(centos(Some("CentOS Stream")), SupportStatus::Supported);
+        assert_eq!(centos(None), SupportStatus::Unknown);
+        assert_eq!(
+            table
+                .find("centos", Some("CentOS Stream"), &version("9"))
+                .unwrap()
+                .eol,
+            date("2027-05-31")
+        );
+    }
+
+    #[test]
+    fn test_update_table() {
+        let mut table = LifecycleTable::default();
+        table.insert(Release {
+            id: "debian".into(),
+            name: None,
+            version: "10".into(),
+            codename: Some("buster".into()),
+            eol: date("2022-09-10"),
+            extended_eol: Some(date("2029-06-30")),
+        });
+        let fields: HashMap<String, String> = [("ID", "debian"), ("VERSION_ID", "10")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let buster = Distro::from_fields(&fields);
+
+        assert_eq!(
+            table.for_distro_on(&buster, date("2022-01-01")).status,
+            SupportStatus::Supported
+        );
+        assert_eq!(
+            table.for_distro_on(&buster, date("2026-01-01")).status,
+            SupportStatus::ExtendedSupport
+        );
+        assert_eq!(
+            table.for_distro_on(&buster, date("2030-01-01")).status,
+            SupportStatus::EndOfLife
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use super::process::{parse_cgroup, Cgroup};
+use crate::common::utils::read_file;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// cgroup v1 reports "no limit" as the largest page aligned `i64`, which
+/// depends on the page size (eg: `0x7FFF_FFFF_FFFF_0000` with 64 KiB pages).
+/// Anything from this value on is taken as unlimited, up to 1 MiB pages.
+const V1_UNLIMITED: u64 = 0x7FFF_FFFF_FFF0_0000;
+
+/// Resource limits applied to the current process by its cgroup
+/// and CPU affinity mask
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct ResourceLimits {
+    /// cgroup version the limits were read from (1 or 2)
+    pub cgroup_version: Option<u8>,
+    pub cgroup_path: Option<String>,
+    /// Memory limit in bytes, `None` when unlimited
+    pub memory_limit: Option<u64>,
+    /// CPU bandwidth limit in CPUs (quota / period), `None` when unlimited
+    pub cpu_quota: Option<f64>,
+    /// CPUs the cgroup is allowed to run on
+    pub cpuset: Vec<u32>,
+    /// Maximum number of tasks, `None` when unlimited
+    pub pids_limit: Option<u64>,
+    /// Number of CPUs in the `sched_getaffinity` mask of the process
+    pub affinity_cpus: usize,
+    /// CPUs the process can actually use, taking the affinity mask,
+    /// cpuset and CPU quota (rounded up) into account
+    pub effective_cpus: usize,
+}
+
+/// A cgroup filesystem mounted in `/proc/self/mountinfo`
+struct CgroupMount {
+    version: u8,
+    /// Root of the mount inside the cgroup hierarchy
+    root: String,
+    mount_point: PathBuf,
+    /// Controllers of a v1 hierarchy
+    controllers: Vec<String>,
+}
+
+pub fn effective_limits() -> ResourceLimits {
+    //! Get the resource limits that apply to this process.
+    //! Inside a container this gives the container limits,
+    //! where `SystemHardware` reports the host totals.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let limits = systeminfo::effective_limits();
+    //! println!("{} CPUs, {:?} bytes", limits.effective_cpus, limits.memory_limit);
+    //! ```
+    let cgroups = read_file("/proc/self/cgroup")
+        .map(|c| parse_cgroup(&c))
+        .unwrap_or_default();
+    let mounts = read_file("/proc/self/mountinfo")
+        .map(|m| parse_cgroup_mounts(&m))
+        .unwrap_or_default();
+    limits_from(&cgroups, &mounts, affinity_cpus())
+}
+
+fn limits_from(cgroups: &[Cgroup], mounts: &[CgroupMount], affinity_cpus: usize) -> ResourceLimits {
+    let v2 = cgroups.iter().find(|c| c.hierarchy == 0).and_then(|c| {
+        let mount = mounts.iter().find(|m| m.version == 2)?;
+        Some((mount, c))
+    });
+    let v1 = |controller: &str| {
+        let cgroup = cgroups
+            .iter()
+            .find(|c| c.controllers.iter().any(|c| c == controller))?;
+        let mount = mounts
+            .iter()
+            .find(|m| m.version == 1 && m.controllers.iter().any(|c| c == controller))?;
+        Some((mount, cgroup))
+    };
+    // A controller is served by v1 when it is mounted there (hybrid setups),
+    // by the unified hierarchy otherwise.
+    let dirs = |controller: &str| -> Vec<PathBuf> {
+        v1(controller)
+            .or(v2)
+            .map(|(mount, cgroup)| ancestors(mount, &cgroup.path))
+            .unwrap_or_default()
+    };
+    let is_v1 = |controller: &str| v1(controller).is_some();
+    let read_min = |controller: &str, parse: &dyn Fn(&Path) -> Option<u64>| {
+        dirs(controller).iter().filter_map(|d| parse(d)).min()
+    };
+
+    let memory_limit = if is_v1("memory") {
+        read_min("memory", &|d| {
+            read_u64(&d.join("memory.limit_in_bytes")).filter(|&v| v < V1_UNLIMITED)
+        })
+    } else {
+        read_min("memory", &|d| read_u64(&d.join("memory.max")))
+    };
+
+    let cpu_quota = if is_v1("cpu") {
+        dirs("cpu")
+            .iter()
+            .filter_map(|d| {
+                let quota = read_file(d.join("cpu.cfs_quota_us"))?.parse::<i64>().ok()?;
+                let period = read_u64(&d.join("cpu.cfs_period_us"))?;
+                (quota > 0 && period > 0).then(|| quota as f64 / period as f64)
+            })
+            .reduce(f64::min)
+    } else {
+        dirs("cpu")
+            .iter()
+            .filter_map(|d| {
+                // eg: `max 100000` or `50000 100000`
+                let max = read_file(d.join("cpu.max"))?;
+                let (quota, period) = max.split_once(' ')?;
+                let quota = quota.parse::<u64>().ok()?;
+                let period = period.parse::<u64>().ok().filter(|&p| p > 0)?;
+                Some(quota as f64 / period as f64)
+            })
+            .reduce(f64::min)
+    };
+
+    let cpuset_file = if is_v1("cpuset") {
+        "cpuset.effective_cpus"
+    } else {
+        "cpuset.cpus.effective"
+    };
+    let cpuset = dirs("cpuset")
+        .first()
+        .and_then(|d| read_file(d.join(cpuset_file)))
+        .map(|c| parse_cpu_list(&c))
+        .unwrap_or_default();
+
+    let pids_limit = read_min("pids", &|d| read_u64(&d.join("pids.max")));
+
+    let mut effective_cpus = affinity_cpus;
+    if !cpuset.is_empty() {
+        effective_cpus = effective_cpus.min(cpuset.len());
+    }
+    if let Some(quota) = cpu_quota {
+        effective_cpus = effective_cpus.min(quota.ceil() as usize);
+    }
+
+    let (cgroup_version, cgroup_path) = match v1("memory").or_else(|| v1("cpu")) {
+        Some((_, c)) => (Some(1), Some(c.path.clone())),
+        None => (v2.map(|_| 2), v2.map(|(_, c)| c.path.clone())),
+    };
+
+    ResourceLimits {
+        cgroup_version,
+        cgroup_path,
+        memory_limit,
+        cpu_quota,
+        cpuset,
+        pids_limit,
+        affinity_cpus,
+        effective_cpus: effective_cpus.max(1),
+    }
+}
+
+fn ancestors(mount: &CgroupMount, path: &str) -> Vec<PathBuf> {
+    //! Directories of the cgroup and of its parents, up to the mount point.
+    //! With cgroup namespaces the cgroup path is relative to the mount root.
+    let relative = path
+        .strip_prefix(mount.root.as_str())
+        .unwrap_or(path)
+        .trim_start_matches('/');
+    let dir = mount.mount_point.join(relative);
+    let dir = if dir.is_dir() {
+        dir
+    } else {
+        mount.mount_point.clone()
+    };
+    dir.ancestors()
+        .take_while(|d| d.starts_with(&mount.mount_point))
+        .map(Path::to_path_buf)
+        .collect()
+}
+
+fn read_u64(path: &Path) -> Option<u64> {
+    //! Read a numeric limit, `max` meaning unlimited
+    read_file(path).and_then(|v| v.parse().ok())
+}
+
+fn parse_cgroup_mounts(mountinfo: &str) -> Vec<CgroupMount> {
+    // eg: 36 32 0:32 / /sys/fs/cgroup/memory rw,relatime - cgroup cgroup rw,memory
+    mountinfo
+        .lines()
+        .filter_map(|l| {
+            let (mount, fs) = l.split_once(" - ")?;
+            let mount: Vec<&str> = mount.split_whitespace().collect();
+            let fs: Vec<&str> = fs.split_whitespace().collect();
+            let version = match *fs.first()? {
+                "cgroup2" => 2,
+                "cgroup" => 1,
+                _ => return None,
+            };
+            Some(CgroupMount {
+                version,
+                root: mount.get(3)?.to_string(),
+                mount_point: PathBuf::from(mount.get(4)?),
+                controllers: fs
+                    .get(2)
+                    .map(|o| o.split(',').map(Into::into).collect())
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+fn parse_cpu_list(list: &str) -> Vec<u32> {
+    //! Parse a kernel CPU list like `0-3,8,10-11`
+    list.trim()
+        .split(',')
+        .filter_map(|range| match range.split_once('-') {
+            Some((start, end)) => Some(start.parse().ok()?..=end.parse().ok()?),
+            None => {
+                let cpu = range.parse().ok()?;
+                Some(cpu..=cpu)
+            }
+        })
+        .flatten()
+        .collect()
+}
+
+fn affinity_cpus() -> usize {
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    let rc =
+        unsafe { libc::sched_getaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &mut set) };
+    if rc == 0 {
+        unsafe { libc::CPU_COUNT(&set) as usize }
+    } else {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixture::Fixture;
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parse_cpu_list("5"), vec![5]);
+        assert!(parse_cpu_list("").is_empty());
+    }
+
+    #[test]
+    fn test_limits_cgroup_v2() {
+        let fixture = Fixture::new("cgv2");
+        let root = fixture.root();
+        let pod = root.join("kubepods.slice");
+        let app = pod.join("app.scope");
+        fixture.write(pod.join("memory.max"), "1073741824\n");
+        fixture.write(app.join("memory.max"), "max\n");
+        fixture.write(pod.join("cpu.max"), "max 100000\n");
+        fixture.write(app.join("cpu.max"), "150000 100000\n");
+        fixture.write(app.join("cpuset.cpus.effective"), "0-5\n");
+        fixture.write(app.join("pids.max"), "512\n");
+
+        let mountinfo = format!(
+            "30 23 0:26 / {} rw,nosuid - cgroup2 cgroup2 rw,nsdelegate\n",
+            root.display()
+        );
+        let limits = limits_from(
+            &parse_cgroup("0::/kubepods.slice/app.scope\n"),
+            &parse_cgroup_mounts(&mountinfo),
+            8,
+        );
+
+        assert_eq!(limits.cgroup_version, Some(2));
+        assert_eq!(limits.memory_limit, Some(1073741824));
+        assert_eq!(limits.cpu_quota, Some(1.5));
+        assert_eq!(limits.cpuset.len(), 6);
+        assert_eq!(limits.pids_limit, Some(512));
+        assert_eq!(limits.effective_cpus, 2);
+    }
+
+    #[test]
+    fn test_limits_cgroup_v1() {
+        let fixture = Fixture::new("cgv1");
+        let root = fixture.root();
+        let memory = root.join("memory/docker/abc");
+        let cpu = root.join("cpu/docker/abc");
+        fixture.write(memory.join("memory.limit_in_bytes"), "536870912\n");
+        fixture.write(
+            root.join("memory/memory.limit_in_bytes"),
+            "9223372036854771712\n",
+        );
+        fixture.write(cpu.join("cpu.cfs_quota_us"), "-1\n");
+        fixture.write(cpu.join("cpu.cfs_period_us"), "100000\n");
+
+        let mountinfo = format!(
+            "36 32 0:32 / {0}/memory rw - cgroup cgroup rw,memory\n33 32 0:29 / {0}/cpu rw - cgroup cgroup rw,cpu,cpuacct\n",
+            root.display()
+        );
+        let cgroup = parse_cgroup("4:memory:/docker/abc\n2:cpu,cpuacct:/docker/abc\n");
+        let limits = limits_from(&cgroup, &parse_cgroup_mounts(&mountinfo), 4);
+        // "No limit" on a kernel with 64 KiB pages
+        fixture.write(
+            memory.join("memory.limit_in_bytes"),
+            "9223372036854710272\n",
+        );
+        let unlimited = limits_from(&cgroup, &parse_cgroup_mounts(&mountinfo), 4);
+
+        assert_eq!(limits.cgroup_version, Some(1));
+        assert_eq!(limits.cgroup_path.as_deref(), Some("/docker/abc"));
+        assert_eq!(limits.memory_limit, Some(536870912));
+        assert_eq!(limits.cpu_quota, None);
+        assert_eq!(limits.pids_limit, None);
+        assert_eq!(limits.effective_cpus, 4);
+        assert_eq!(unlimited.memory_limit, None);
+    }
+}
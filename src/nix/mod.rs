@@ -3,3 +3,92 @@ pub use self::hwinfo::from_system_hardware;
 
 mod osinfo;
 pub use self::osinfo::from_system_os;
+
+mod cpufreq;
+pub use self::cpufreq::{cpu_frequency, CpuFrequency, CpuFrequencyInfo};
+
+mod cpuusage;
+pub use self::cpuusage::{CpuPercent, CpuStat, CpuTimes, CpuUsage, CpuUsageIter};
+
+mod runtime;
+pub use self::runtime::{boot_id, boot_time, load_average, uptime, LoadAverage, Uptime};
+
+mod process;
+pub use self::process::{process, processes, Cgroup, Process, ProcessState};
+
+mod pressure;
+pub use self::pressure::{pressure, Pressure, PressureInfo, PressureLine, StallTime};
+
+mod vmstat;
+pub use self::vmstat::{vmstat, VmStat};
+
+mod diskstats;
+pub use self::diskstats::{DiskIo, DiskStat, DiskStats};
+
+mod dmi;
+
+mod virt;
+pub use self::virt::{virtualization, Virtualization, VirtualizationInfo};
+
+mod container;
+pub use self::container::{container, ContainerInfo, ContainerRuntime};
+
+mod limits;
+pub use self::limits::{effective_limits, ResourceLimits};
+
+mod cloud;
+pub use self::cloud::{cloud, CloudInfo, CloudProvider, MetadataProbe};
+
+mod firmware;
+pub use self::firmware::{firmware, EfiBootEntry, FirmwareInfo, FirmwareMode};
+
+mod security;
+pub use self::security::{
+    security, AppArmorStatus, CpuVulnerability, LockdownMode, SecurityInfo, SelinuxMode, Taint,
+    TaintFlag, VulnerabilityState,
+};
+
+mod tpm;
+pub use self::tpm::{tpm, TpmInfo, TpmVersion};
+
+mod kernel;
+pub use self::kernel::{kernel, KernelInfo};
+
+mod modules;
+pub use self::modules::{kernel_modules, KernelModule, ModuleState};
+
+mod cmdline;
+pub use self::cmdline::{kernel_cmdline, KernelCmdline, KernelParam, ParamValue};
+
+mod kconfig;
+pub use self::kconfig::{kernel_config, ConfigValue, KernelConfig};
+
+mod sysctl;
+pub use self::sysctl::{important_sysctls, sysctl, sysctl_list, Sysctl, SysctlValue};
+
+mod distro;
+pub use self::distro::{distro, Distro, DistroFamily};
+pub use crate::common::Version;
+
+mod os_release;
+pub use self::os_release::os_release;
+
+mod lifecycle;
+pub use self::lifecycle::{distro_lifecycle, Lifecycle, LifecycleTable, Release, SupportStatus};
+
+mod rpmdb;
+
+mod packages;
+pub use self::packages::{packages, Package, PackageManager};
+
+mod boot;
+pub use self::boot::{boot_info, BootEntry, BootInfo, Bootloader, InstalledKernel};
+
+mod init;
+pub use self::init::{init_system, unit_files, InitInfo, InitSystem, UnitFile, UnitFileState};
+
+mod users;
+pub use self::users::{groups, login_history, sessions, users, Group, Session, SessionKind, User};
+
+mod clock;
+pub use self::clock::{clock, timezone, ClockInfo, ClockState, Timezone};
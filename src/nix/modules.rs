@@ -0,0 +1,140 @@
+use crate::common::utils::read_file;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::Path};
+
+const SYSFS_MODULE: &str = "/sys/module";
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum ModuleState {
+    #[default]
+    Live,
+    Loading,
+    Unloading,
+}
+
+/// A module loaded in the running kernel
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct KernelModule {
+    pub name: String,
+    /// Memory size of the module in bytes
+    pub size: u64,
+    /// `None` when the module cannot be unloaded
+    pub refcount: Option<u32>,
+    /// Modules depending on this one
+    pub used_by: Vec<String>,
+    pub state: ModuleState,
+    /// Taint flags of the module, eg: `OE` for an unsigned out-of-tree module
+    pub taint: String,
+    /// `MODULE_VERSION` of the module, if any
+    pub version: Option<String>,
+    /// Module parameters readable by the current user
+    pub parameters: BTreeMap<String, String>,
+}
+
+pub fn kernel_modules() -> Vec<KernelModule> {
+    //! Get the loaded kernel modules from `/proc/modules`,
+    //! with their version and parameters from `/sys/module`.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! for module in systeminfo::kernel_modules() {
+    //!     println!("{} {} used by {:?}", module.name, module.size, module.used_by);
+    //! }
+    //! ```
+    let sysfs = Path::new(SYSFS_MODULE);
+    read_file("/proc/modules")
+        .map(|m| parse_modules(&m))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|module| with_sysfs(module, sysfs))
+        .collect()
+}
+
+fn parse_modules(modules: &str) -> Vec<KernelModule> {
+    // eg: snd_hda_intel 57344 4 snd_hda_codec,snd_hda_core, Live 0x0000000000000000 (OE)
+    modules
+        .lines()
+        .filter_map(|l| {
+            let mut fields = l.split_whitespace();
+            let name = fields.next()?.to_string();
+            let size = fields.next()?.parse().ok()?;
+            let refcount = fields.next()?.parse().ok();
+            let used_by = fields
+                .next()?
+                .split(',')
+                .filter(|m| !m.is_empty() && *m != "-")
+                .map(Into::into)
+                .collect();
+            let state = match fields.next()? {
+                "Loading" => ModuleState::Loading,
+                "Unloading" => ModuleState::Unloading,
+                _ => ModuleState::Live,
+            };
+            let taint = fields
+                .nth(1)
+                .map(|t| t.trim_matches(|c| c == '(' || c == ')').to_string())
+                .unwrap_or_default();
+            Some(KernelModule {
+                name,
+                size,
+                refcount,
+                used_by,
+                state,
+                taint,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+fn with_sysfs(mut module: KernelModule, sysfs: &Path) -> KernelModule {
+    let dir = sysfs.join(&module.name);
+    module.version = read_file(dir.join("version"));
+    module.parameters = fs::read_dir(dir.join("parameters"))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    let name = e.file_name().into_string().ok()?;
+                    // Write-only and root-only parameters are skipped
+                    let value = fs::read_to_string(e.path()).ok()?;
+                    Some((name, value.trim().to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    module
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixture::Fixture;
+
+    #[test]
+    fn test_modules() {
+        let modules = parse_modules(
+            "snd_hda_intel 57344 4 snd_hda_codec,snd_hda_core, Live 0x0000000000000000 (OE)\n\
+             nvme 45056 3 - Live 0x0000000000000000\n\
+             zfs 5480448 - - Loading 0x0000000000000000 (POE)\n",
+        );
+        assert_eq!(modules.len(), 3);
+        assert_eq!(modules[0].used_by, vec!["snd_hda_codec", "snd_hda_core"]);
+        assert_eq!(modules[0].taint, "OE");
+        assert_eq!(modules[1].refcount, Some(3));
+        assert!(modules[1].used_by.is_empty());
+        assert_eq!(modules[1].taint, "");
+        assert_eq!(modules[2].refcount, None);
+        assert_eq!(modules[2].state, ModuleState::Loading);
+
+        let fixture = Fixture::new("mod");
+        let root = fixture.root();
+        fixture.dir(root.join("nvme/parameters"));
+        fixture.write(root.join("nvme/version"), "1.0\n");
+        fixture.write(root.join("nvme/parameters/max_host_mem_size_mb"), "128\n");
+        let nvme = with_sysfs(modules[1].clone(), root);
+
+        assert_eq!(nvme.version.as_deref(), Some("1.0"));
+        assert_eq!(nvme.parameters["max_host_mem_size_mb"], "128");
+    }
+}
@@ -0,0 +1,217 @@
+use crate::common::utils::read_file;
+use std::{collections::HashMap, path::Path};
+
+pub fn os_release() -> Option<HashMap<String, String>> {
+    //! Get the `os-release(5)` fields of the running system, from
+    //! `/etc/os-release` or `/usr/lib/os-release`. Systems predating
+    //! os-release get equivalent fields (`ID`, `NAME`, `VERSION_ID`, ...)
+    //! from `/etc/lsb-release`, `/etc/redhat-release`,
+    //! `/etc/alpine-release` or `/etc/debian_version`.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let os_release = systeminfo::os_release().unwrap();
+    //! println!("{:?}", os_release.get("PRETTY_NAME"));
+    //! ```
+    from_root(Path::new("/"))
+}
+
+fn from_root(root: &Path) -> Option<HashMap<String, String>> {
+    let read = |path: &str| read_file(root.join(path));
+    if let Some(os_release) = read("etc/os-release").or_else(|| read("usr/lib/os-release")) {
+        return Some(parse_os_release(&os_release));
+    }
+    read("etc/lsb-release")
+        .map(|l| from_lsb_release(&l))
+        .filter(|f| f.contains_key("ID"))
+        .or_else(|| read("etc/redhat-release").map(|r| from_redhat_release(&r)))
+        .or_else(|| {
+            read("etc/alpine-release").map(|version| {
+                fields(&[
+                    ("ID", "alpine"),
+                    ("NAME", "Alpine Linux"),
+                    ("VERSION_ID", &version),
+                    ("PRETTY_NAME", &format!("Alpine Linux v{}", version)),
+                ])
+            })
+        })
+        .or_else(|| read("etc/debian_version").map(|d| from_debian_version(&d)))
+}
+
+pub(crate) fn parse_os_release(content: &str) -> HashMap<String, String> {
+    //! Parse the shell compatible `KEY=value` assignments of an os-release
+    //! file: comments, single and double quotes and backslash escapes are
+    //! handled like the shell would, variable expansion is not supported.
+    content
+        .lines()
+        .filter_map(|l| {
+            let l = l.trim();
+            if l.starts_with('#') {
+                return None;
+            }
+            let (key, value) = l.split_once('=')?;
+            let valid_key = key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+                && key.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_');
+            valid_key.then(|| (key.to_string(), unquote(value)))
+        })
+        .collect()
+}
+
+fn unquote(value: &str) -> String {
+    let mut out = String::new();
+    let mut quote = None;
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), c) if c == q => quote = None,
+            (Some('\''), c) => out.push(c),
+            // Inside double quotes only these characters can be escaped
+            (Some(_), '\\') => match chars.next() {
+                Some(e @ ('"' | '\\' | '$' | '`')) => out.push(e),
+                Some(e) => {
+                    out.push('\\');
+                    out.push(e);
+                }
+                None => out.push('\\'),
+            },
+            (None, '\\') => out.extend(chars.next()),
+            // Unquoted whitespace ends the value, anything after is a comment
+            (None, c) if c.is_whitespace() => break,
+            (_, c) => out.push(c),
+        }
+    }
+    out
+}
+
+fn fields(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs
+        .iter()
+        .filter(|(_, v)| !v.is_empty())
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+fn from_lsb_release(content: &str) -> HashMap<String, String> {
+    // eg: DISTRIB_ID=Ubuntu DISTRIB_RELEASE=14.04 DISTRIB_CODENAME=trusty
+    let lsb = parse_os_release(content);
+    let get = |key: &str| lsb.get(key).map(String::as_str).unwrap_or_default();
+    let name = get("DISTRIB_ID");
+    fields(&[
+        ("ID", &name.to_lowercase()),
+        ("NAME", name),
+        ("VERSION_ID", get("DISTRIB_RELEASE")),
+        ("VERSION_CODENAME", get("DISTRIB_CODENAME")),
+        ("PRETTY_NAME", get("DISTRIB_DESCRIPTION")),
+    ])
+}
+
+fn from_redhat_release(content: &str) -> HashMap<String, String> {
+    // eg: CentOS Linux release 7.9.2009 (Core)
+    //     Red Hat Enterprise Linux Server release 6.10 (Santiago)
+    let line = content.lines().next().unwrap_or_default().trim();
+    let (name, rest) = line.split_once(" release ").unwrap_or((line, ""));
+    let version = rest.split_whitespace().next().unwrap_or_default();
+    let id = match name.split_whitespace().next().unwrap_or_default() {
+        "Red" => "rhel".to_string(),
+        "CentOS" => "centos".to_string(),
+        "Fedora" => "fedora".to_string(),
+        "Scientific" => "scientific".to_string(),
+        other => other.to_lowercase(),
+    };
+    // Drop the build number of CentOS releases, eg: 7.9.2009
+    let version_id = version.split('.').take(2).collect::<Vec<_>>().join(".");
+    fields(&[
+        ("ID", &id),
+        ("ID_LIKE", if id == "fedora" { "" } else { "rhel fedora" }),
+        ("NAME", name),
+        ("VERSION", rest.trim()),
+        ("VERSION_ID", &version_id),
+        ("PRETTY_NAME", line),
+    ])
+}
+
+fn from_debian_version(content: &str) -> HashMap<String, String> {
+    // eg: 12.4 on stable releases, bookworm/sid on testing and unstable
+    let version = content.trim();
+    let (version_id, codename) = if version.starts_with(|c: char| c.is_ascii_digit()) {
+        (version.split('.').next().unwrap_or_default(), "")
+    } else {
+        ("", version.split('/').next().unwrap_or_default())
+    };
+    fields(&[
+        ("ID", "debian"),
+        ("NAME", "Debian GNU/Linux"),
+        ("VERSION", version),
+        ("VERSION_ID", version_id),
+        ("VERSION_CODENAME", codename),
+        ("PRETTY_NAME", &format!("Debian GNU/Linux {}", version)),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixture::Fixture;
+
+    #[test]
+    fn test_parse_os_release() {
+        let fields = parse_os_release(
+            "# comment\n\nNAME=\"Fancy \\\"Linux\\\"\"\nID=fancy\n\
+             PRETTY_NAME='Fancy $HOME \\n'\nVERSION=\"1.0 \\$5 \\\\ \\`x\\`\"\n\
+             HOME_URL=\"https://example.org/?a=b&c=d\"\nVARIANT=Server # trailing\n\
+             BUILD_ID=a\\ b\n1INVALID=x\n",
+        );
+        assert_eq!(fields["NAME"], "Fancy \"Linux\"");
+        assert_eq!(fields["ID"], "fancy");
+        assert_eq!(fields["PRETTY_NAME"], "Fancy $HOME \\n");
+        assert_eq!(fields["VERSION"], "1.0 $5 \\ `x`");
+        assert_eq!(fields["HOME_URL"], "https://example.org/?a=b&c=d");
+        assert_eq!(fields["VARIANT"], "Server");
+        assert_eq!(fields["BUILD_ID"], "a b");
+        assert!(!fields.contains_key("1INVALID"));
+    }
+
+    #[test]
+    fn test_legacy_release_files() {
+        let rhel = from_redhat_release("Red Hat Enterprise Linux Server release 6.10 (Santiago)\n");
+        assert_eq!(rhel["ID"], "rhel");
+        assert_eq!(rhel["VERSION_ID"], "6.10");
+        assert_eq!(rhel["NAME"], "Red Hat Enterprise Linux Server");
+
+        let centos = from_redhat_release("CentOS Linux release 7.9.2009 (Core)");
+        assert_eq!(centos["ID"], "centos");
+        assert_eq!(centos["VERSION_ID"], "7.9");
+
+        let sid = from_debian_version("bookworm/sid\n");
+        assert_eq!(sid["VERSION_CODENAME"], "bookworm");
+        assert!(!sid.contains_key("VERSION_ID"));
+        assert_eq!(from_debian_version("12.4")["VERSION_ID"], "12");
+
+        let lsb = from_lsb_release(
+            "DISTRIB_ID=Ubuntu\nDISTRIB_RELEASE=14.04\nDISTRIB_CODENAME=trusty\n\
+             DISTRIB_DESCRIPTION=\"Ubuntu 14.04.6 LTS\"\n",
+        );
+        assert_eq!(lsb["ID"], "ubuntu");
+        assert_eq!(lsb["PRETTY_NAME"], "Ubuntu 14.04.6 LTS");
+    }
+
+    #[test]
+    fn test_from_root_fallbacks() {
+        let fixture = Fixture::new("osr");
+        let root = fixture.root();
+        fixture.dir(root.join("etc"));
+        fixture.dir(root.join("usr/lib"));
+        fixture.write(root.join("etc/alpine-release"), "3.19.1\n");
+        let alpine = from_root(root).unwrap();
+        fixture.write(
+            root.join("usr/lib/os-release"),
+            "ID=fedora\nVERSION_ID=39\n",
+        );
+        let fedora = from_root(root).unwrap();
+
+        assert_eq!(alpine["ID"], "alpine");
+        assert_eq!(alpine["VERSION_ID"], "3.19.1");
+        assert_eq!(fedora["ID"], "fedora");
+    }
+}
@@ -6,9 +6,16 @@ use crate::{
         OSArchitecture,
     },
     consts::SystemOS,
+    humanize::Humanize,
 };
 use std::collections::HashMap;
 
+use super::{
+    kernel::kernel,
+    os_release::os_release,
+    runtime::{boot_id, boot_time, load_average, uptime},
+};
+
 pub fn from_system_os() -> SystemOS {
     //! Get Operating System Information
     //! ## Example Usage:
@@ -19,7 +26,7 @@ pub fn from_system_os() -> SystemOS {
     //! ```
     let v = vec![
         std::thread::spawn(from_hostnamectl),
-        std::thread::spawn(from_os_release),
+        std::thread::spawn(os_release),
     ];
     let mut it = v.into_iter().filter_map(|x| x.join().ok());
 
@@ -47,12 +54,23 @@ pub fn from_system_os() -> SystemOS {
 
     SystemOS {
         os,
-        kernel: hostnamectl.get("Kernel").cloned().unwrap_or_default(),
+        os_id: os_release.get("ID").cloned().unwrap_or_default(),
+        version_id: os_release.get("VERSION_ID").cloned().unwrap_or_default(),
+        kernel: kernel()
+            .map(|k| format!("{} {}", k.sysname, k.release))
+            .or_else(|| hostnamectl.get("Kernel").cloned())
+            .unwrap_or_default(),
         architecture: OSArchitecture::get_arch().to_string(),
         hostname: hostname::get().unwrap_or_default(),
         version,
         edition,
         ip_address: get_local_ip().unwrap_or_default(),
+        uptime: uptime().map(|u| u.uptime.humanize()).unwrap_or_default(),
+        boot_time: boot_time().map(|t| t.humanize()).unwrap_or_default(),
+        boot_id: boot_id().unwrap_or_default(),
+        load_average: load_average()
+            .map(|l| format!("{:.2} {:.2} {:.2}", l.one, l.five, l.fifteen))
+            .unwrap_or_default(),
     }
 }
 
@@ -64,12 +82,3 @@ fn from_hostnamectl() -> Option<HashMap<String, String>> {
         Some(to_hashmap_with_delim(output, ':'))
     }
 }
-
-fn from_os_release() -> Option<HashMap<String, String>> {
-    let output = exec_command_with_args("cat", &["/etc/os-release"]).unwrap_or_default();
-    if output.is_empty() || output.contains("command not found") {
-        None
-    } else {
-        Some(to_hashmap_with_delim(output, '='))
-    }
-}
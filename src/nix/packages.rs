@@ -0,0 +1,242 @@
+use super::rpmdb::{
+    read_headers, TagValue, RPMTAG_ARCH, RPMTAG_EPOCH, RPMTAG_LONGSIZE, RPMTAG_NAME,
+    RPMTAG_RELEASE, RPMTAG_SIZE, RPMTAG_SOURCERPM, RPMTAG_VERSION,
+};
+use crate::common::utils::read_file;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fs, path::Path};
+
+/// Package database a package was read from
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PackageManager {
+    Dpkg,
+    Apk,
+    Pacman,
+    Rpm,
+}
+
+/// An installed package
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Package {
+    pub name: String,
+    /// Full version as the package manager compares it,
+    /// eg: `1:2.34-1ubuntu1` or `1:9.0.2120-1.el9`
+    pub version: String,
+    pub architecture: Option<String>,
+    /// Source package, eg: `glibc` for `libc6` or `bash-5.1.8-6.el9.src.rpm`
+    pub source: Option<String>,
+    /// Installed size in bytes
+    pub installed_size: Option<u64>,
+    pub manager: PackageManager,
+}
+
+pub fn packages() -> Vec<Package> {
+    //! Get the installed packages by reading the package databases
+    //! of dpkg, apk, pacman and rpm directly.
+    //! The BerkeleyDB rpm database of RHEL 8 and older is not supported.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! for package in systeminfo::packages() {
+    //!     println!("{} {} {:?}", package.name, package.version, package.architecture);
+    //! }
+    //! ```
+    from_root(Path::new("/"))
+}
+
+fn from_root(root: &Path) -> Vec<Package> {
+    let mut packages = Vec::new();
+    if let Some(status) = read_file(root.join("var/lib/dpkg/status")) {
+        packages.extend(parse_dpkg_status(&status));
+    }
+    if let Some(installed) = read_file(root.join("lib/apk/db/installed")) {
+        packages.extend(parse_apk_installed(&installed));
+    }
+    if let Ok(entries) = fs::read_dir(root.join("var/lib/pacman/local")) {
+        packages.extend(
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| read_file(e.path().join("desc")))
+                .filter_map(|desc| parse_pacman_desc(&desc)),
+        );
+    }
+    // /var/lib/rpm is a symlink to /usr/lib/sysimage/rpm on newer releases
+    let rpm = ["usr/lib/sysimage/rpm", "var/lib/rpm"]
+        .iter()
+        .map(|dir| read_headers(&root.join(dir)))
+        .find(|headers| !headers.is_empty())
+        .unwrap_or_default();
+    packages.extend(rpm.iter().filter_map(rpm_package));
+    packages
+}
+
+fn parse_dpkg_status(status: &str) -> Vec<Package> {
+    status
+        .split("\n\n")
+        .filter_map(|stanza| {
+            // Continuation lines (eg: Description, Conffiles) start with a space
+            let fields: HashMap<&str, &str> = stanza
+                .lines()
+                .filter(|l| !l.starts_with([' ', '\t']))
+                .filter_map(|l| l.split_once(':'))
+                .map(|(k, v)| (k, v.trim()))
+                .collect();
+            // eg: `install ok installed`, `deinstall ok config-files`
+            if !fields.get("Status")?.ends_with(" installed") {
+                return None;
+            }
+            let name = fields.get("Package")?.to_string();
+            Some(Package {
+                version: fields.get("Version")?.to_string(),
+                architecture: fields.get("Architecture").map(|a| a.to_string()),
+                // eg: `Source: glibc (2.36-9)` when the versions differ
+                source: fields
+                    .get("Source")
+                    .and_then(|s| s.split_whitespace().next())
+                    .map(Into::into),
+                installed_size: fields
+                    .get("Installed-Size")
+                    .and_then(|s| s.parse::<u64>().ok())
+                    .map(|kib| kib * 1024),
+                name,
+                manager: PackageManager::Dpkg,
+            })
+        })
+        .collect()
+}
+
+fn parse_apk_installed(installed: &str) -> Vec<Package> {
+    // One letter keys, eg: P:musl V:1.2.4-r2 A:x86_64 o:musl I:647168
+    installed
+        .split("\n\n")
+        .filter_map(|block| {
+            let fields: HashMap<&str, &str> =
+                block.lines().filter_map(|l| l.split_once(':')).collect();
+            Some(Package {
+                name: fields.get("P")?.to_string(),
+                version: fields.get("V")?.to_string(),
+                architecture: fields.get("A").map(|a| a.to_string()),
+                source: fields.get("o").map(|o| o.to_string()),
+                installed_size: fields.get("I").and_then(|i| i.parse().ok()),
+                manager: PackageManager::Apk,
+            })
+        })
+        .collect()
+}
+
+fn parse_pacman_desc(desc: &str) -> Option<Package> {
+    // %NAME%\nbash\n\n%VERSION%\n5.2.026-2\n\n...
+    let fields: HashMap<&str, &str> = desc
+        .split("\n\n")
+        .filter_map(|section| {
+            let (key, value) = section.trim().split_once('\n')?;
+            Some((key.trim_matches('%'), value.lines().next()?.trim()))
+        })
+        .collect();
+    Some(Package {
+        name: fields.get("NAME")?.to_string(),
+        version: fields.get("VERSION")?.to_string(),
+        architecture: fields.get("ARCH").map(|a| a.to_string()),
+        source: fields.get("BASE").map(|b| b.to_string()),
+        installed_size: fields.get("SIZE").and_then(|s| s.parse().ok()),
+        manager: PackageManager::Pacman,
+    })
+}
+
+fn rpm_package(tags: &HashMap<u32, TagValue>) -> Option<Package> {
+    let string = |tag: u32| match tags.get(&tag) {
+        Some(TagValue::Str(s)) => Some(s.clone()),
+        _ => None,
+    };
+    let int = |tag: u32| match tags.get(&tag) {
+        Some(TagValue::Int(i)) => Some(*i),
+        _ => None,
+    };
+    let name = string(RPMTAG_NAME)?;
+    // Imported signing keys are stored as packages
+    if name == "gpg-pubkey" {
+        return None;
+    }
+    let version = string(RPMTAG_VERSION)?;
+    let version = match (int(RPMTAG_EPOCH), string(RPMTAG_RELEASE)) {
+        (Some(epoch), Some(release)) => format!("{}:{}-{}", epoch, version, release),
+        (None, Some(release)) => format!("{}-{}", version, release),
+        (Some(epoch), None) => format!("{}:{}", epoch, version),
+        (None, None) => version,
+    };
+    Some(Package {
+        name,
+        version,
+        architecture: string(RPMTAG_ARCH),
+        source: string(RPMTAG_SOURCERPM),
+        installed_size: int(RPMTAG_LONGSIZE).or_else(|| int(RPMTAG_SIZE)),
+        manager: PackageManager::Rpm,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dpkg_status() {
+        let packages = parse_dpkg_status(
+            "Package: libc6\nStatus: install ok installed\nPriority: optional\n\
+             Installed-Size: 12\nArchitecture: amd64\nSource: glibc (2.36-9)\n\
+             Version: 2.36-9+deb12u4\nDescription: GNU C Library\n continuation: line\n\n\
+             Package: oldpkg\nStatus: deinstall ok config-files\nVersion: 1.0\n\n\
+             Package: tzdata\nStatus: install ok installed\nArchitecture: all\nVersion: 2024a-0+deb12u1\n",
+        );
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].name, "libc6");
+        assert_eq!(packages[0].source.as_deref(), Some("glibc"));
+        assert_eq!(packages[0].installed_size, Some(12 * 1024));
+        assert_eq!(packages[1].version, "2024a-0+deb12u1");
+        assert_eq!(packages[1].source, None);
+    }
+
+    #[test]
+    fn test_apk_and_pacman() {
+        let apk = parse_apk_installed(
+            "C:Q1abc=\nP:musl\nV:1.2.4-r2\nA:x86_64\nS:383152\nI:647168\no:musl\n\n\
+             C:Q1def=\nP:busybox\nV:1.36.1-r15\nA:x86_64\nI:958464\no:busybox\n",
+        );
+        assert_eq!(apk.len(), 2);
+        assert_eq!(apk[0].installed_size, Some(647168));
+        assert_eq!(apk[1].name, "busybox");
+
+        let pacman = parse_pacman_desc(
+            "%NAME%\nbash\n\n%VERSION%\n5.2.026-2\n\n%BASE%\nbash\n\n\
+             %ARCH%\nx86_64\n\n%SIZE%\n9277440\n\n%LICENSE%\nGPL-3.0-or-later\n",
+        )
+        .unwrap();
+        assert_eq!(pacman.version, "5.2.026-2");
+        assert_eq!(pacman.architecture.as_deref(), Some("x86_64"));
+        assert_eq!(pacman.installed_size, Some(9277440));
+    }
+
+    #[test]
+    fn test_rpm_package() {
+        let tags: HashMap<u32, TagValue> = [
+            (RPMTAG_NAME, TagValue::Str("vim-minimal".into())),
+            (RPMTAG_VERSION, TagValue::Str("9.0.2120".into())),
+            (RPMTAG_RELEASE, TagValue::Str("1.el9".into())),
+            (RPMTAG_EPOCH, TagValue::Int(2)),
+            (RPMTAG_ARCH, TagValue::Str("x86_64".into())),
+            (RPMTAG_SIZE, TagValue::Int(1_500_000)),
+        ]
+        .into_iter()
+        .collect();
+        let package = rpm_package(&tags).unwrap();
+        assert_eq!(package.version, "2:9.0.2120-1.el9");
+        assert_eq!(package.installed_size, Some(1_500_000));
+
+        let key: HashMap<u32, TagValue> = [
+            (RPMTAG_NAME, TagValue::Str("gpg-pubkey".into())),
+            (RPMTAG_VERSION, TagValue::Str("fd431d51".into())),
+        ]
+        .into_iter()
+        .collect();
+        assert!(rpm_package(&key).is_none());
+    }
+}
@@ -0,0 +1,140 @@
+use crate::common::utils::read_file;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// One line (`some` or `full`) of a `/proc/pressure/*` file
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct PressureLine {
+    /// Percentage of time stalled over the last 10 seconds
+    pub avg10: f64,
+    /// Percentage of time stalled over the last 60 seconds
+    pub avg60: f64,
+    /// Percentage of time stalled over the last 300 seconds
+    pub avg300: f64,
+    /// Total stall time in microseconds
+    pub total: u64,
+}
+
+/// Pressure stall information of a single resource
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Pressure {
+    /// Some tasks were stalled on the resource
+    pub some: PressureLine,
+    /// All non-idle tasks were stalled on the resource at the same time.
+    /// Not reported for CPU on kernels before 5.13.
+    pub full: Option<PressureLine>,
+}
+
+/// Pressure stall information (PSI) for CPU, memory and I/O
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct PressureInfo {
+    pub cpu: Option<Pressure>,
+    pub memory: Option<Pressure>,
+    pub io: Option<Pressure>,
+}
+
+/// Percentage of time stalled between two pressure readings
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct StallTime {
+    pub some: f64,
+    pub full: Option<f64>,
+}
+
+pub fn pressure() -> PressureInfo {
+    //! Get the pressure stall information from `/proc/pressure`.
+    //! Resources are `None` when the kernel is built without
+    //! `CONFIG_PSI` or booted with `psi=0`.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let psi = systeminfo::pressure();
+    //! println!("{:#?}", psi.memory);
+    //! ```
+    let read =
+        |r: &str| read_file(format!("/proc/pressure/{}", r)).and_then(|s| parse_pressure(&s));
+    PressureInfo {
+        cpu: read("cpu"),
+        memory: read("memory"),
+        io: read("io"),
+    }
+}
+
+impl Pressure {
+    pub fn stall_since(&self, prev: &Self, elapsed: Duration) -> StallTime {
+        //! Percentage of `elapsed` spent stalled since the `prev` reading,
+        //! computed from the cumulative `total` counters.
+        let pct = |cur: u64, prev: u64| {
+            let elapsed = elapsed.as_micros() as f64;
+            if elapsed > 0. {
+                (cur.saturating_sub(prev) as f64 * 100. / elapsed).min(100.)
+            } else {
+                0.
+            }
+        };
+        StallTime {
+            some: pct(self.some.total, prev.some.total),
+            full: match (self.full, prev.full) {
+                (Some(cur), Some(prev)) => Some(pct(cur.total, prev.total)),
+                _ => None,
+            },
+        }
+    }
+}
+
+fn parse_pressure_line(line: &str) -> Option<PressureLine> {
+    // eg: avg10=3.44 avg60=3.35 avg300=2.38 total=19670857
+    let mut pressure = PressureLine::default();
+    for (key, val) in line.split_whitespace().filter_map(|kv| kv.split_once('=')) {
+        match key {
+            "avg10" => pressure.avg10 = val.parse().ok()?,
+            "avg60" => pressure.avg60 = val.parse().ok()?,
+            "avg300" => pressure.avg300 = val.parse().ok()?,
+            "total" => pressure.total = val.parse().ok()?,
+            _ => {}
+        }
+    }
+    Some(pressure)
+}
+
+fn parse_pressure(content: &str) -> Option<Pressure> {
+    let mut some = None;
+    let mut full = None;
+    for line in content.lines() {
+        if let Some(l) = line.strip_prefix("some ") {
+            some = parse_pressure_line(l);
+        } else if let Some(l) = line.strip_prefix("full ") {
+            full = parse_pressure_line(l);
+        }
+    }
+    Some(Pressure { some: some?, full })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pressure() {
+        let psi = parse_pressure(
+            "some avg10=3.44 avg60=3.35 avg300=2.38 total=19670857\nfull avg10=0.00 avg60=0.04 avg300=0.14 total=2250189\n",
+        )
+        .unwrap();
+        assert_eq!(psi.some.avg10, 3.44);
+        assert_eq!(psi.some.avg300, 2.38);
+        assert_eq!(psi.some.total, 19670857);
+        assert_eq!(psi.full.unwrap().avg60, 0.04);
+
+        let cpu = parse_pressure("some avg10=0.00 avg60=0.00 avg300=0.00 total=10\n").unwrap();
+        assert!(cpu.full.is_none());
+        assert!(parse_pressure("").is_none());
+    }
+
+    #[test]
+    fn test_stall_since() {
+        let prev = parse_pressure("some avg10=0.00 avg60=0.00 avg300=0.00 total=1000000\nfull avg10=0.00 avg60=0.00 avg300=0.00 total=0\n").unwrap();
+        let cur = parse_pressure("some avg10=0.00 avg60=0.00 avg300=0.00 total=1500000\nfull avg10=0.00 avg60=0.00 avg300=0.00 total=100000\n").unwrap();
+        let stall = cur.stall_since(&prev, Duration::from_secs(2));
+        assert_eq!(stall.some, 25.);
+        assert_eq!(stall.full, Some(5.));
+    }
+}
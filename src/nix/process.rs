@@ -0,0 +1,417 @@
+use super::runtime::boot_time;
+use crate::common::utils::read_file;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::CStr,
+    fs,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+/// Scheduling state of a process, see `proc(5)`
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum ProcessState {
+    Running,
+    Sleeping,
+    /// Uninterruptible sleep, usually waiting on I/O
+    DiskSleep,
+    Zombie,
+    Stopped,
+    TracingStop,
+    Dead,
+    Idle,
+    Parked,
+    #[default]
+    Unknown,
+}
+
+/// A control group membership line of `/proc/[pid]/cgroup`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cgroup {
+    /// Hierarchy ID, always `0` for the cgroup v2 unified hierarchy
+    pub hierarchy: u32,
+    /// Controllers bound to the hierarchy, empty for cgroup v2
+    pub controllers: Vec<String>,
+    pub path: String,
+}
+
+/// Process information from `/proc/[pid]`
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct Process {
+    pub pid: u32,
+    pub ppid: u32,
+    pub name: String,
+    pub cmdline: Vec<String>,
+    /// Executable path, `None` for kernel threads or without permission
+    pub exe: Option<String>,
+    pub state: ProcessState,
+    pub uid: u32,
+    pub gid: u32,
+    pub user: Option<String>,
+    pub group: Option<String>,
+    pub start_time: Option<SystemTime>,
+    pub threads: u32,
+    /// Resident set size in bytes
+    pub rss: u64,
+    /// Virtual memory size in bytes
+    pub vsz: u64,
+    pub user_time: Duration,
+    pub system_time: Duration,
+    pub nice: i32,
+    pub cgroups: Vec<Cgroup>,
+    /// Namespace inode numbers by namespace type (eg: `mnt`, `net`, `pid`)
+    pub namespaces: BTreeMap<String, u64>,
+}
+
+impl From<char> for ProcessState {
+    fn from(c: char) -> Self {
+        match c {
+            'R' => Self::Running,
+            'S' => Self::Sleeping,
+            'D' => Self::DiskSleep,
+            'Z' => Self::Zombie,
+            'T' => Self::Stopped,
+            't' => Self::TracingStop,
+            'X' | 'x' => Self::Dead,
+            'I' => Self::Idle,
+            'P' => Self::Parked,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// Values of `/proc/[pid]/stat` that are used to build a [Process]
+struct Stat {
+    name: String,
+    state: ProcessState,
+    ppid: u32,
+    utime: u64,
+    stime: u64,
+    nice: i32,
+    threads: u32,
+    starttime: u64,
+    vsize: u64,
+    rss_pages: u64,
+}
+
+pub fn processes() -> Vec<Process> {
+    //! List all processes running on the system.
+    //! Processes exiting while the list is being built are left out.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! for p in systeminfo::processes() {
+    //!     println!("{} {} {:?}", p.pid, p.name, p.cmdline);
+    //! }
+    //! ```
+    from_procfs(Path::new("/proc"), boot_time())
+}
+
+pub fn process(pid: u32) -> Option<Process> {
+    //! Get the information of a single process
+    let root = Path::new("/proc");
+    process_from_dir(
+        pid,
+        &root.join(pid.to_string()),
+        boot_time(),
+        &mut Names::default(),
+    )
+}
+
+fn from_procfs(root: &Path, boot_time: Option<SystemTime>) -> Vec<Process> {
+    // Most processes share a handful of owners, resolve each one once
+    let mut names = Names::default();
+    let mut procs: Vec<Process> = fs::read_dir(root)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    let pid = e.file_name().to_str()?.parse::<u32>().ok()?;
+                    process_from_dir(pid, &e.path(), boot_time, &mut names)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    procs.sort_by_key(|p| p.pid);
+    procs
+}
+
+fn process_from_dir(
+    pid: u32,
+    dir: &Path,
+    boot_time: Option<SystemTime>,
+    names: &mut Names,
+) -> Option<Process> {
+    // `stat` and `status` are mandatory: if they are gone, so is the process
+    let stat = parse_stat(&fs::read_to_string(dir.join("stat")).ok()?)?;
+    let status = fs::read_to_string(dir.join("status")).ok()?;
+    let uid = status_id(&status, "Uid:")?;
+    let gid = status_id(&status, "Gid:")?;
+    let clk_tck = clock_ticks();
+
+    Some(Process {
+        pid,
+        ppid: stat.ppid,
+        name: stat.name,
+        cmdline: fs::read(dir.join("cmdline"))
+            .map(|c| parse_cmdline(&c))
+            .unwrap_or_default(),
+        exe: fs::read_link(dir.join("exe"))
+            .ok()
+            .map(|p| p.to_string_lossy().into_owned()),
+        state: stat.state,
+        uid,
+        gid,
+        user: names.user(uid),
+        group: names.group(gid),
+        start_time: boot_time.map(|b| b + ticks_to_duration(stat.starttime, clk_tck)),
+        threads: stat.threads,
+        rss: stat.rss_pages * page_size(),
+        vsz: stat.vsize,
+        user_time: ticks_to_duration(stat.utime, clk_tck),
+        system_time: ticks_to_duration(stat.stime, clk_tck),
+        nice: stat.nice,
+        cgroups: read_file(dir.join("cgroup"))
+            .map(|c| parse_cgroup(&c))
+            .unwrap_or_default(),
+        namespaces: namespaces(&dir.join("ns")),
+    })
+}
+
+fn parse_stat(content: &str) -> Option<Stat> {
+    // The name is enclosed in parentheses and may itself contain spaces or
+    // parentheses, so split at the last closing one.
+    let open = content.find('(')?;
+    let close = content.rfind(')')?;
+    let name = content.get(open + 1..close)?.to_string();
+    let fields: Vec<&str> = content.get(close + 1..)?.split_whitespace().collect();
+    let field = |i: usize| fields.get(i).and_then(|v| v.parse::<u64>().ok());
+
+    Some(Stat {
+        name,
+        state: fields.first()?.chars().next()?.into(),
+        ppid: field(1)? as u32,
+        utime: field(11)?,
+        stime: field(12)?,
+        nice: fields.get(16)?.parse().ok()?,
+        threads: field(17)? as u32,
+        starttime: field(19)?,
+        vsize: field(20)?,
+        rss_pages: field(21)?,
+    })
+}
+
+fn parse_cmdline(content: &[u8]) -> Vec<String> {
+    content
+        .split(|&b| b == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect()
+}
+
+pub(crate) fn parse_cgroup(content: &str) -> Vec<Cgroup> {
+    // eg: 4:memory:/user.slice or 0::/user.slice/user-1000.slice
+    content
+        .lines()
+        .filter_map(|l| {
+            let mut parts = l.splitn(3, ':');
+            let hierarchy = parts.next()?.parse().ok()?;
+            let controllers = parts
+                .next()?
+                .split(',')
+                .filter(|c| !c.is_empty())
+                .map(Into::into)
+                .collect();
+            Some(Cgroup {
+                hierarchy,
+                controllers,
+                path: parts.next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+fn status_id(status: &str, key: &str) -> Option<u32> {
+    //! Real user/group ID from a `Uid:`/`Gid:` line of `/proc/[pid]/status`
+    status
+        .lines()
+        .find_map(|l| l.strip_prefix(key))
+        .and_then(|ids| ids.split_whitespace().next())
+        .and_then(|id| id.parse().ok())
+}
+
+fn namespaces(dir: &Path) -> BTreeMap<String, u64> {
+    // Each entry is a symlink like `net:[4026531833]`
+    fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    let target = fs::read_link(e.path()).ok()?;
+                    let inode = target
+                        .to_str()?
+                        .split_once(":[")?
+                        .1
+                        .trim_end_matches(']')
+                        .parse()
+                        .ok()?;
+                    Some((e.file_name().to_str()?.to_string(), inode))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn ticks_to_duration(ticks: u64, clk_tck: u64) -> Duration {
+    Duration::from_millis(ticks * 1000 / clk_tck.max(1))
+}
+
+fn clock_ticks() -> u64 {
+    match unsafe { libc::sysconf(libc::_SC_CLK_TCK) } {
+        n if n > 0 => n as u64,
+        _ => 100,
+    }
+}
+
+fn page_size() -> u64 {
+    match unsafe { libc::sysconf(libc::_SC_PAGESIZE) } {
+        n if n > 0 => n as u64,
+        _ => 4096,
+    }
+}
+
+/// uid and gid to name cache, filled by NSS lookups
+#[derive(Default)]
+struct Names {
+    users: HashMap<u32, Option<String>>,
+    groups: HashMap<u32, Option<String>>,
+}
+
+impl Names {
+    fn user(&mut self, uid: u32) -> Option<String> {
+        self.users
+            .entry(uid)
+            .or_insert_with(|| {
+                let mut pwd: libc::passwd = unsafe { std::mem::zeroed() };
+                let mut result = std::ptr::null_mut();
+                nss_lookup(|buffer, len| unsafe {
+                    match libc::getpwuid_r(uid, &mut pwd, buffer, len, &mut result) {
+                        0 if !result.is_null() => Ok(Some(pwd.pw_name)),
+                        0 => Ok(None),
+                        rc => Err(rc),
+                    }
+                })
+            })
+            .clone()
+    }
+
+    fn group(&mut self, gid: u32) -> Option<String> {
+        self.groups
+            .entry(gid)
+            .or_insert_with(|| {
+                let mut grp: libc::group = unsafe { std::mem::zeroed() };
+                let mut result = std::ptr::null_mut();
+                nss_lookup(|buffer, len| unsafe {
+                    match libc::getgrgid_r(gid, &mut grp, buffer, len, &mut result) {
+                        0 if !result.is_null() => Ok(Some(grp.gr_name)),
+                        0 => Ok(None),
+                        rc => Err(rc),
+                    }
+                })
+            })
+            .clone()
+    }
+}
+
+fn nss_lookup<F>(mut lookup: F) -> Option<String>
+where
+    F: FnMut(*mut libc::c_char, usize) -> Result<Option<*mut libc::c_char>, libc::c_int>,
+{
+    //! Run a `get*_r` lookup, growing the buffer while it fails with `ERANGE`
+    let mut buffer = vec![0 as libc::c_char; 1024];
+    loop {
+        match lookup(buffer.as_mut_ptr(), buffer.len()) {
+            Ok(name) => {
+                return name.map(|n| unsafe { CStr::from_ptr(n) }.to_string_lossy().into_owned())
+            }
+            Err(libc::ERANGE) if buffer.len() < 1 << 20 => buffer.resize(buffer.len() * 2, 0),
+            Err(_) => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixture::Fixture;
+
+    #[test]
+    fn test_parse_stat() {
+        let stat = parse_stat(
+            "3545 (tmux: server (1)) S 3541 3545 3541 0 -1 4194304 82 0 0 0 150 30 0 0 20 -5 4 0 211213 2703360 313 18446744073709551615",
+        )
+        .unwrap();
+        assert_eq!(stat.name, "tmux: server (1)");
+        assert_eq!(stat.state, ProcessState::Sleeping);
+        assert_eq!(stat.ppid, 3541);
+        assert_eq!(stat.utime, 150);
+        assert_eq!(stat.stime, 30);
+        assert_eq!(stat.nice, -5);
+        assert_eq!(stat.threads, 4);
+        assert_eq!(stat.starttime, 211213);
+        assert_eq!(stat.vsize, 2703360);
+        assert_eq!(stat.rss_pages, 313);
+        assert!(parse_stat("3545 (cat) R").is_none());
+    }
+
+    #[test]
+    fn test_parse_cgroup() {
+        let cgroups = parse_cgroup("4:memory:/docker/abc\n2:cpu,cpuacct:/\n0::/user.slice\n");
+        assert_eq!(cgroups.len(), 3);
+        assert_eq!(cgroups[0].controllers, vec!["memory"]);
+        assert_eq!(cgroups[1].controllers, vec!["cpu", "cpuacct"]);
+        assert_eq!(cgroups[2].hierarchy, 0);
+        assert!(cgroups[2].controllers.is_empty());
+        assert_eq!(cgroups[2].path, "/user.slice");
+    }
+
+    #[test]
+    fn test_current_process() {
+        let pid = std::process::id();
+        let me = process(pid).unwrap();
+        assert_eq!(me.pid, pid);
+        assert!(me.threads >= 1);
+        assert!(!me.cmdline.is_empty());
+        assert!(processes().iter().any(|p| p.pid == pid));
+        assert!(process(u32::MAX).is_none());
+    }
+
+    #[test]
+    fn test_names() {
+        let mut names = Names::default();
+        assert_eq!(names.user(0).as_deref(), Some("root"));
+        assert_eq!(names.group(0).as_deref(), Some("root"));
+        assert_eq!(names.user(u32::MAX - 1), None);
+        assert_eq!(names.users.len(), 2);
+    }
+
+    #[test]
+    fn test_process_without_status() {
+        let fixture = Fixture::new("proc");
+        let dir = fixture.root();
+        fixture.write(
+            dir.join("stat"),
+            "42 (gone) Z 1 42 42 0 -1 0 0 0 0 0 0 0 0 0 20 0 1 0 100 0 0",
+        );
+        let gone = process_from_dir(42, dir, None, &mut Names::default());
+        fixture.write(
+            dir.join("status"),
+            "Name:\tgone\nUid:\t1000\t1000\t1000\t1000\nGid:\t100\t100\t100\t100\n",
+        );
+        let alive = process_from_dir(42, dir, None, &mut Names::default()).unwrap();
+
+        assert!(gone.is_none());
+        assert_eq!((alive.uid, alive.gid), (1000, 100));
+    }
+}
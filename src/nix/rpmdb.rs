@@ -0,0 +1,430 @@
+// Read-only access to the header blobs of the RPM database, without
+// linking librpm or SQLite. Supports the `rpmdb.sqlite` backend (Fedora 33+,
+// RHEL 9+) and the `Packages.db` ndb backend (openSUSE, SLES 15 SP3+).
+// The BerkeleyDB `Packages` backend of older releases is not supported.
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::Path,
+};
+
+const NDB_HEADER_MAGIC: u32 = u32::from_le_bytes(*b"RpmP");
+const NDB_SLOT_MAGIC: u32 = u32::from_le_bytes(*b"Slot");
+const NDB_BLOB_MAGIC: u32 = u32::from_le_bytes(*b"BlbS");
+const NDB_PAGE_SIZE: usize = 4096;
+const NDB_BLK_SIZE: usize = 16;
+
+const RPM_INT16: u32 = 3;
+const RPM_INT32: u32 = 4;
+const RPM_INT64: u32 = 5;
+const RPM_STRING: u32 = 6;
+const RPM_I18NSTRING: u32 = 9;
+
+pub(crate) const RPMTAG_NAME: u32 = 1000;
+pub(crate) const RPMTAG_VERSION: u32 = 1001;
+pub(crate) const RPMTAG_RELEASE: u32 = 1002;
+pub(crate) const RPMTAG_EPOCH: u32 = 1003;
+pub(crate) const RPMTAG_SIZE: u32 = 1009;
+pub(crate) const RPMTAG_ARCH: u32 = 1022;
+pub(crate) const RPMTAG_SOURCERPM: u32 = 1044;
+pub(crate) const RPMTAG_LONGSIZE: u32 = 5009;
+
+/// Value of a header tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TagValue {
+    Int(u64),
+    Str(String),
+}
+
+pub(crate) fn read_headers(dir: &Path) -> Vec<HashMap<u32, TagValue>> {
+    //! Read and parse the package headers of the database in `dir`
+    let blobs = if dir.join("rpmdb.sqlite").is_file() {
+        fs::read(dir.join("rpmdb.sqlite"))
+            .ok()
+            .and_then(|db| sqlite_packages(&db))
+    } else {
+        fs::read(dir.join("Packages.db"))
+            .ok()
+            .and_then(|db| ndb_packages(&db))
+    };
+    blobs
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|b| parse_header(b))
+        .collect()
+}
+
+fn be_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn le_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+pub(crate) fn parse_header(blob: &[u8]) -> Option<HashMap<u32, TagValue>> {
+    //! Parse an RPM header blob: index count and data length (big endian),
+    //! 16 byte index entries (tag, type, offset, count), then the data store
+    let il = be_u32(blob, 0)? as usize;
+    let dl = be_u32(blob, 4)? as usize;
+    let data = blob.get(8 + il * 16..8 + il * 16 + dl)?;
+    let mut tags = HashMap::new();
+    for entry in blob.get(8..8 + il * 16)?.chunks_exact(16) {
+        let (tag, kind, offset) = (be_u32(entry, 0)?, be_u32(entry, 4)?, be_u32(entry, 8)?);
+        let offset = offset as usize;
+        let value = match kind {
+            RPM_INT16 => data
+                .get(offset..offset + 2)
+                .map(|v| TagValue::Int(u16::from_be_bytes([v[0], v[1]]) as u64)),
+            RPM_INT32 => be_u32(data, offset).map(|v| TagValue::Int(v as u64)),
+            RPM_INT64 => data
+                .get(offset..offset + 8)
+                .and_then(|v| v.try_into().ok())
+                .map(|v| TagValue::Int(u64::from_be_bytes(v))),
+            RPM_STRING | RPM_I18NSTRING => data.get(offset..).map(|s| {
+                let end = s.iter().position(|&b| b == 0).unwrap_or(s.len());
+                TagValue::Str(String::from_utf8_lossy(&s[..end]).into_owned())
+            }),
+            _ => None,
+        };
+        if let Some(value) = value {
+            tags.insert(tag, value);
+        }
+    }
+    Some(tags)
+}
+
+fn ndb_packages(db: &[u8]) -> Option<Vec<Vec<u8>>> {
+    // File header: magic, version, generation, number of slot pages
+    if le_u32(db, 0)? != NDB_HEADER_MAGIC {
+        return None;
+    }
+    let slot_pages = le_u32(db, 12)? as usize;
+    let slots_end = (slot_pages * NDB_PAGE_SIZE).min(db.len());
+    // The file header takes the place of the first two slots
+    let blobs = db
+        .get(32..slots_end)?
+        .chunks_exact(16)
+        .filter_map(|slot| {
+            let (magic, pkg_idx, blk_offset) =
+                (le_u32(slot, 0)?, le_u32(slot, 4)?, le_u32(slot, 8)?);
+            if magic != NDB_SLOT_MAGIC || pkg_idx == 0 {
+                return None;
+            }
+            // Blob header: magic, package index, checksum, length
+            let start = blk_offset as usize * NDB_BLK_SIZE;
+            if le_u32(db, start)? != NDB_BLOB_MAGIC {
+                return None;
+            }
+            let len = le_u32(db, start + 12)? as usize;
+            db.get(start + 16..start + 16 + len).map(<[u8]>::to_vec)
+        })
+        .collect();
+    Some(blobs)
+}
+
+/// Minimal reader for the table b-trees of an SQLite 3 database file
+struct Sqlite<'a> {
+    db: &'a [u8],
+    page_size: usize,
+    usable: usize,
+}
+
+impl<'a> Sqlite<'a> {
+    fn new(db: &'a [u8]) -> Option<Self> {
+        if !db.starts_with(b"SQLite format 3\0") {
+            return None;
+        }
+        let page_size = match u16::from_be_bytes([*db.get(16)?, *db.get(17)?]) {
+            1 => 65536,
+            size => size as usize,
+        };
+        let reserved = *db.get(20)? as usize;
+        if page_size < 512 || page_size - reserved < 480 {
+            return None;
+        }
+        Some(Self {
+            db,
+            page_size,
+            usable: page_size - reserved,
+        })
+    }
+
+    fn page(&self, number: u32) -> Option<&'a [u8]> {
+        let start = (number as usize).checked_sub(1)? * self.page_size;
+        self.db.get(start..start + self.page_size)
+    }
+
+    fn rows(&self, root: u32) -> Vec<Vec<u8>> {
+        //! Payloads of all the rows of the table rooted at page `root`
+        let mut rows = Vec::new();
+        let mut pages = vec![root];
+        // Pages already read, a corrupted file may link them in a loop
+        let mut visited = HashSet::new();
+        while let Some(number) = pages.pop() {
+            if !visited.insert(number) {
+                continue;
+            }
+            let page = match self.page(number) {
+                Some(page) => page,
+                None => continue,
+            };
+            let header = if number == 1 { 100 } else { 0 };
+            let kind = page[header];
+            let cells = u16::from_be_bytes([page[header + 3], page[header + 4]]) as usize;
+            let pointers = header + if kind == 0x05 { 12 } else { 8 };
+            for i in 0..cells {
+                let cell = match page.get(pointers + i * 2..pointers + i * 2 + 2) {
+                    Some(p) => u16::from_be_bytes([p[0], p[1]]) as usize,
+                    None => break,
+                };
+                match kind {
+                    0x05 => pages.extend(be_u32(page, cell)),
+                    0x0d => rows.extend(self.leaf_payload(page, cell)),
+                    _ => {}
+                }
+            }
+            if kind == 0x05 {
+                pages.extend(be_u32(page, header + 8));
+            }
+        }
+        rows
+    }
+
+    fn leaf_payload(&self, page: &[u8], cell: usize) -> Option<Vec<u8>> {
+        let (len, n) = varint(page.get(cell..)?);
+        let (_rowid, m) = varint(page.get(cell + n..)?);
+        let start = cell + n + m;
+        let len = len as usize;
+        let max_local = self.usable - 35;
+        let local = if len <= max_local {
+            len
+        } else {
+            let min_local = (self.usable - 12) * 32 / 255 - 23;
+            let k = min_local + (len - min_local) % (self.usable - 4);
+            if k <= max_local {
+                k
+            } else {
+                min_local
+            }
+        };
+        let mut payload = page.get(start..start + local)?.to_vec();
+        let mut next = if local < len {
+            be_u32(page, start + local)?
+        } else {
+            0
+        };
+        while next != 0 && payload.len() < len {
+            let overflow = self.page(next)?;
+            let take = (len - payload.len()).min(self.usable - 4);
+            payload.extend_from_slice(overflow.get(4..4 + take)?);
+            next = be_u32(overflow, 0)?;
+        }
+        Some(payload)
+    }
+}
+
+fn varint(data: &[u8]) -> (u64, usize) {
+    let mut value = 0u64;
+    for (i, &b) in data.iter().enumerate().take(9) {
+        if i == 8 {
+            return ((value << 8) | b as u64, 9);
+        }
+        value = (value << 7) | (b & 0x7f) as u64;
+        if b & 0x80 == 0 {
+            return (value, i + 1);
+        }
+    }
+    (value, data.len().min(9))
+}
+
+/// Column of a record
+#[derive(Debug, PartialEq)]
+enum Column<'a> {
+    Null,
+    Int(i64),
+    Blob(&'a [u8]),
+    Text(&'a [u8]),
+}
+
+fn record(payload: &[u8]) -> Option<Vec<Column<'_>>> {
+    //! Columns of a record, `None` for corrupt records
+    let (header_len, mut pos) = varint(payload);
+    let mut types = Vec::new();
+    while pos < header_len as usize && pos < payload.len() {
+        let (t, n) = varint(&payload[pos..]);
+        types.push(t);
+        pos += n;
+    }
+    let mut offset = header_len as usize;
+    types
+        .into_iter()
+        .map(|t| {
+            let size = match t {
+                0 | 8 | 9 => 0,
+                1..=4 => t as usize,
+                5 => 6,
+                6 | 7 => 8,
+                t if t >= 12 => ((t - 12) / 2) as usize,
+                // 10 and 11 are reserved
+                _ => return None,
+            };
+            let end = offset.checked_add(size)?;
+            let value = payload.get(offset..end).unwrap_or_default();
+            offset = end;
+            Some(match t {
+                0 => Column::Null,
+                8 => Column::Int(0),
+                9 => Column::Int(1),
+                1..=6 => {
+                    // Big endian two's complement integer
+                    let sign = if value.first().is_some_and(|&b| b & 0x80 != 0) {
+                        -1i64
+                    } else {
+                        0
+                    };
+                    Column::Int(value.iter().fold(sign, |acc, &b| (acc << 8) | b as i64))
+                }
+                t if t >= 12 && t % 2 == 0 => Column::Blob(value),
+                t if t >= 13 => Column::Text(value),
+                _ => Column::Null,
+            })
+        })
+        .collect()
+}
+
+fn sqlite_packages(db: &[u8]) -> Option<Vec<Vec<u8>>> {
+    //! Blobs of the `Packages (hnum INTEGER PRIMARY KEY, blob BLOB)` table.
+    //! Changes still in the write-ahead log (`rpmdb.sqlite-wal`) are not seen.
+    let sqlite = Sqlite::new(db)?;
+    // sqlite_schema: type, name, tbl_name, rootpage, sql
+    let root = sqlite
+        .rows(1)
+        .iter()
+        .find_map(|row| match record(row)?[..] {
+            [Column::Text(b"table"), Column::Text(b"Packages"), _, Column::Int(root), ..] => {
+                Some(root as u32)
+            }
+            _ => None,
+        })?;
+    let blobs = sqlite
+        .rows(root)
+        .iter()
+        .filter_map(|row| match record(row)?.get(1) {
+            Some(Column::Blob(blob)) => Some(blob.to_vec()),
+            _ => None,
+        })
+        .collect();
+    Some(blobs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header_blob(tags: &[(u32, &str)], size: u32) -> Vec<u8> {
+        let mut index = Vec::new();
+        let mut data = Vec::new();
+        for (tag, value) in tags {
+            index.extend(tag.to_be_bytes());
+            index.extend(RPM_STRING.to_be_bytes());
+            index.extend((data.len() as u32).to_be_bytes());
+            index.extend(1u32.to_be_bytes());
+            data.extend(value.as_bytes());
+            data.push(0);
+        }
+        while data.len() % 4 != 0 {
+            data.push(0);
+        }
+        index.extend(RPMTAG_SIZE.to_be_bytes());
+        index.extend(RPM_INT32.to_be_bytes());
+        index.extend((data.len() as u32).to_be_bytes());
+        index.extend(1u32.to_be_bytes());
+        data.extend(size.to_be_bytes());
+
+        let mut blob = Vec::new();
+        blob.extend(((index.len() / 16) as u32).to_be_bytes());
+        blob.extend((data.len() as u32).to_be_bytes());
+        blob.extend(index);
+        blob.extend(data);
+        blob
+    }
+
+    #[test]
+    fn test_parse_header() {
+        let blob = header_blob(
+            &[(RPMTAG_NAME, "bash"), (RPMTAG_VERSION, "5.1.8")],
+            7_000_000,
+        );
+        let tags = parse_header(&blob).unwrap();
+        assert_eq!(tags[&RPMTAG_NAME], TagValue::Str("bash".into()));
+        assert_eq!(tags[&RPMTAG_VERSION], TagValue::Str("5.1.8".into()));
+        assert_eq!(tags[&RPMTAG_SIZE], TagValue::Int(7_000_000));
+    }
+
+    #[test]
+    fn test_ndb_packages() {
+        let blob = header_blob(&[(RPMTAG_NAME, "zypper")], 1);
+        let mut db = vec![0u8; NDB_PAGE_SIZE];
+        db[0..4].copy_from_slice(b"RpmP");
+        db[12..16].copy_from_slice(&1u32.to_le_bytes());
+        // One slot pointing at the block right after the slot page
+        let blk = (NDB_PAGE_SIZE / NDB_BLK_SIZE) as u32;
+        db[32..36].copy_from_slice(b"Slot");
+        db[36..40].copy_from_slice(&1u32.to_le_bytes());
+        db[40..44].copy_from_slice(&blk.to_le_bytes());
+        db.extend(b"BlbS");
+        db.extend(1u32.to_le_bytes());
+        db.extend(0u32.to_le_bytes());
+        db.extend((blob.len() as u32).to_le_bytes());
+        db.extend(&blob);
+
+        assert_eq!(ndb_packages(&db), Some(vec![blob]));
+    }
+
+    #[test]
+    fn test_sqlite_record() {
+        assert_eq!(varint(&[0x81, 0x00]), (128, 2));
+        // header length 3, NULL, 2 byte blob, then the blob
+        let payload = [3, 0, 16, 0xab, 0xcd];
+        assert_eq!(
+            record(&payload),
+            Some(vec![Column::Null, Column::Blob(&[0xab, 0xcd])])
+        );
+        assert_eq!(record(&[2, 1, 0xff]), Some(vec![Column::Int(-1)]));
+        // Reserved serial types and sizes past the address space
+        assert_eq!(record(&[2, 10]), None);
+        assert_eq!(record(&[2, 11]), None);
+        let mut huge = vec![19];
+        huge.extend([0xff; 18]);
+        assert_eq!(record(&huge), None);
+    }
+
+    #[test]
+    fn test_sqlite_page_loop() {
+        let mut db = vec![0u8; 512 * 3];
+        db[..16].copy_from_slice(b"SQLite format 3\0");
+        db[16..18].copy_from_slice(&512u16.to_be_bytes());
+        // Interior page 2, its cell and right-most pointer lead to page 3 and itself
+        let page2 = 512;
+        db[page2] = 0x05;
+        db[page2 + 3..page2 + 5].copy_from_slice(&1u16.to_be_bytes());
+        db[page2 + 8..page2 + 12].copy_from_slice(&2u32.to_be_bytes());
+        db[page2 + 12..page2 + 14].copy_from_slice(&200u16.to_be_bytes());
+        db[page2 + 200..page2 + 204].copy_from_slice(&3u32.to_be_bytes());
+        db[page2 + 204] = 1;
+        // Leaf page 3 with a single row
+        let page3 = 1024;
+        db[page3] = 0x0d;
+        db[page3 + 3..page3 + 5].copy_from_slice(&1u16.to_be_bytes());
+        db[page3 + 8..page3 + 10].copy_from_slice(&100u16.to_be_bytes());
+        db[page3 + 100..page3 + 105].copy_from_slice(&[3, 1, 2, 1, 7]);
+
+        let sqlite = Sqlite::new(&db).unwrap();
+        assert_eq!(sqlite.rows(2), vec![vec![2, 1, 7]]);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::common::utils::read_file;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// System load averages from `/proc/loadavg`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct LoadAverage {
+    pub one: f64,
+    pub five: f64,
+    pub fifteen: f64,
+    /// Number of currently runnable scheduling entities
+    pub runnable_tasks: u32,
+    /// Number of scheduling entities that currently exist on the system
+    pub total_tasks: u32,
+    pub last_pid: u32,
+}
+
+/// System uptime from `/proc/uptime`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Uptime {
+    pub uptime: Duration,
+    /// Sum of the time each CPU has spent idle
+    pub idle: Duration,
+}
+
+pub fn load_average() -> Option<LoadAverage> {
+    //! Get the 1, 5 and 15 minute load averages and the task counts
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let load = systeminfo::load_average();
+    //! println!("{:#?}", load);
+    //! ```
+    read_file("/proc/loadavg").and_then(|s| parse_loadavg(&s))
+}
+
+pub fn uptime() -> Option<Uptime> {
+    //! Get the time elapsed since boot and the accumulated idle time
+    read_file("/proc/uptime").and_then(|s| parse_uptime(&s))
+}
+
+pub fn boot_time() -> Option<SystemTime> {
+    //! Get the time the system booted, from `btime` in `/proc/stat`
+    read_file("/proc/stat").and_then(|s| parse_btime(&s))
+}
+
+pub fn boot_id() -> Option<String> {
+    //! Get the random UUID the kernel generates on every boot
+    read_file("/proc/sys/kernel/random/boot_id")
+}
+
+fn parse_loadavg(content: &str) -> Option<LoadAverage> {
+    // eg: 0.41 0.23 0.09 1/72 3100
+    let mut fields = content.split_whitespace();
+    let one = fields.next()?.parse().ok()?;
+    let five = fields.next()?.parse().ok()?;
+    let fifteen = fields.next()?.parse().ok()?;
+    let (runnable, total) = fields.next()?.split_once('/')?;
+    Some(LoadAverage {
+        one,
+        five,
+        fifteen,
+        runnable_tasks: runnable.parse().ok()?,
+        total_tasks: total.parse().ok()?,
+        last_pid: fields.next()?.parse().ok()?,
+    })
+}
+
+fn parse_uptime(content: &str) -> Option<Uptime> {
+    // eg: 2065.55 1965.82
+    let mut fields = content.split_whitespace();
+    let uptime = fields.next()?.parse::<f64>().ok()?;
+    let idle = fields.next()?.parse::<f64>().ok()?;
+    Some(Uptime {
+        uptime: Duration::from_secs_f64(uptime),
+        idle: Duration::from_secs_f64(idle),
+    })
+}
+
+fn parse_btime(content: &str) -> Option<SystemTime> {
+    content
+        .lines()
+        .find_map(|l| l.strip_prefix("btime "))
+        .and_then(|secs| secs.trim().parse::<u64>().ok())
+        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_loadavg() {
+        let load = parse_loadavg("0.41 0.23 0.09 1/72 3100\n").unwrap();
+        assert_eq!(load.one, 0.41);
+        assert_eq!(load.fifteen, 0.09);
+        assert_eq!(load.runnable_tasks, 1);
+        assert_eq!(load.total_tasks, 72);
+        assert_eq!(load.last_pid, 3100);
+        assert!(parse_loadavg("0.41 0.23").is_none());
+    }
+
+    #[test]
+    fn test_parse_uptime_and_btime() {
+        let uptime = parse_uptime("2065.55 1965.82\n").unwrap();
+        assert_eq!(uptime.uptime.as_millis(), 2065550);
+        assert_eq!(uptime.idle.as_secs(), 1965);
+
+        let btime = parse_btime("cpu  1 2 3 4\nctxt 5\nbtime 1610859829\n").unwrap();
+        assert_eq!(btime, UNIX_EPOCH + Duration::from_secs(1610859829));
+    }
+}
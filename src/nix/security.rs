@@ -0,0 +1,340 @@
+use crate::common::utils::read_file;
+use core::fmt::{Display, Formatter, Result};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+/// Taint flags of `/proc/sys/kernel/tainted`, indexed by bit
+const TAINT_FLAGS: [(char, &str); 20] = [
+    ('P', "proprietary module was loaded"),
+    ('F', "module was force loaded"),
+    ('S', "kernel running on an out of specification system"),
+    ('R', "module was force unloaded"),
+    ('M', "processor reported a Machine Check Exception"),
+    ('B', "bad page referenced or unexpected page flags"),
+    ('U', "taint requested by userspace"),
+    ('D', "kernel died recently (OOPS or BUG)"),
+    ('A', "ACPI table overridden by user"),
+    ('W', "kernel issued a warning"),
+    ('C', "staging driver was loaded"),
+    ('I', "workaround for a platform firmware bug applied"),
+    ('O', "externally built (out-of-tree) module was loaded"),
+    ('E', "unsigned module was loaded"),
+    ('L', "soft lockup occurred"),
+    ('K', "kernel has been live patched"),
+    ('X', "auxiliary taint, defined by distributions"),
+    ('T', "kernel was built with the struct randomization plugin"),
+    ('N', "an in-kernel test has been run"),
+    ('J', "userspace used a mutating debug operation in fwctl"),
+];
+
+/// SELinux mode, as reported by `getenforce`
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum SelinuxMode {
+    #[default]
+    Disabled,
+    Permissive,
+    Enforcing,
+    /// selinuxfs is present but `enforce` can't be read
+    Unknown,
+}
+
+impl Display for SelinuxMode {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match *self {
+            Self::Disabled => write!(f, "disabled"),
+            Self::Permissive => write!(f, "permissive"),
+            Self::Enforcing => write!(f, "enforcing"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Kernel lockdown mode, see `kernel_lockdown(7)`
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum LockdownMode {
+    #[default]
+    None,
+    Integrity,
+    Confidentiality,
+}
+
+impl Display for LockdownMode {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match *self {
+            Self::None => write!(f, "none"),
+            Self::Integrity => write!(f, "integrity"),
+            Self::Confidentiality => write!(f, "confidentiality"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AppArmorStatus {
+    pub enabled: bool,
+    /// Profiles loaded in enforce mode, `None` when the list is not readable (requires root)
+    pub enforce_profiles: Option<usize>,
+    /// Profiles loaded in complain mode, `None` when the list is not readable (requires root)
+    pub complain_profiles: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum VulnerabilityState {
+    NotAffected,
+    Mitigated,
+    Vulnerable,
+    #[default]
+    Unknown,
+}
+
+/// An entry of `/sys/devices/system/cpu/vulnerabilities`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CpuVulnerability {
+    /// eg: `spectre_v2`
+    pub name: String,
+    pub state: VulnerabilityState,
+    /// Status reported by the kernel, eg: `Mitigation: PTI`
+    pub status: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TaintFlag {
+    pub bit: u8,
+    /// Letter used for the flag in oops reports
+    pub code: char,
+    pub description: String,
+}
+
+/// Decoded `/proc/sys/kernel/tainted` bitmask
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Taint {
+    pub mask: u64,
+    pub flags: Vec<TaintFlag>,
+}
+
+impl Taint {
+    pub fn from_mask(mask: u64) -> Self {
+        let flags = (0..64u8)
+            .filter(|&bit| mask & (1 << bit) != 0)
+            .map(|bit| match TAINT_FLAGS.get(bit as usize) {
+                Some(&(code, description)) => TaintFlag {
+                    bit,
+                    code,
+                    description: description.to_string(),
+                },
+                None => TaintFlag {
+                    bit,
+                    code: '?',
+                    description: format!("unknown taint flag {}", bit),
+                },
+            })
+            .collect();
+        Self { mask, flags }
+    }
+
+    pub fn is_tainted(&self) -> bool {
+        self.mask != 0
+    }
+}
+
+/// Security posture of the running kernel
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SecurityInfo {
+    /// Active Linux Security Modules, in initialization order
+    pub lsms: Vec<String>,
+    pub selinux: SelinuxMode,
+    /// SELinux policy type from `/etc/selinux/config` (eg: `targeted`)
+    pub selinux_policy: Option<String>,
+    pub apparmor: AppArmorStatus,
+    pub lockdown: Option<LockdownMode>,
+    pub vulnerabilities: Vec<CpuVulnerability>,
+    /// `None` when `/proc/sys/kernel/tainted` can't be read
+    pub taint: Option<Taint>,
+}
+
+pub fn security() -> SecurityInfo {
+    //! Get the active LSMs, SELinux and AppArmor status, the kernel lockdown
+    //! mode, CPU vulnerability mitigations and the kernel taint flags.
+    //! `lsms` and `lockdown` require `securityfs` to be mounted.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let security = systeminfo::security();
+    //! println!("LSMs: {}", security.lsms.join(","));
+    //! for v in security.vulnerabilities {
+    //!     println!("{}: {}", v.name, v.status);
+    //! }
+    //! ```
+    from_root(Path::new("/"))
+}
+
+fn from_root(root: &Path) -> SecurityInfo {
+    let read = |path: &str| read_file(root.join(path));
+
+    let selinux = if root.join("sys/fs/selinux").is_dir() {
+        match read("sys/fs/selinux/enforce").as_deref() {
+            Some("1") => SelinuxMode::Enforcing,
+            Some("0") => SelinuxMode::Permissive,
+            _ => SelinuxMode::Unknown,
+        }
+    } else {
+        SelinuxMode::Disabled
+    };
+    let selinux_policy = read("etc/selinux/config").and_then(|config| {
+        config.lines().find_map(|l| {
+            l.trim()
+                .strip_prefix("SELINUXTYPE=")
+                .map(|v| v.trim_matches('"').to_string())
+        })
+    });
+
+    let mut vulnerabilities: Vec<CpuVulnerability> =
+        fs::read_dir(root.join("sys/devices/system/cpu/vulnerabilities"))
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .filter_map(|e| {
+                        let name = e.file_name().into_string().ok()?;
+                        let status = read_file(e.path())?;
+                        Some(CpuVulnerability {
+                            name,
+                            state: vulnerability_state(&status),
+                            status,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+    vulnerabilities.sort_by(|a, b| a.name.cmp(&b.name));
+
+    SecurityInfo {
+        lsms: read("sys/kernel/security/lsm")
+            .map(|l| l.split(',').map(Into::into).collect())
+            .unwrap_or_default(),
+        selinux,
+        selinux_policy: selinux_policy.filter(|_| selinux != SelinuxMode::Disabled),
+        apparmor: apparmor(root),
+        lockdown: read("sys/kernel/security/lockdown").and_then(|l| parse_lockdown(&l)),
+        vulnerabilities,
+        taint: read("proc/sys/kernel/tainted")
+            .and_then(|t| t.parse().ok())
+            .map(Taint::from_mask),
+    }
+}
+
+fn apparmor(root: &Path) -> AppArmorStatus {
+    let enabled = read_file(root.join("sys/module/apparmor/parameters/enabled"))
+        .map(|e| e == "Y")
+        .unwrap_or_default();
+    // eg: `/usr/sbin/cupsd (enforce)`
+    let profiles = read_file(root.join("sys/kernel/security/apparmor/profiles"));
+    let count = |mode: &str| {
+        profiles
+            .as_ref()
+            .map(|p| p.lines().filter(|l| l.ends_with(mode)).count())
+    };
+    AppArmorStatus {
+        enabled,
+        enforce_profiles: count("(enforce)"),
+        complain_profiles: count("(complain)"),
+    }
+}
+
+fn parse_lockdown(lockdown: &str) -> Option<LockdownMode> {
+    //! The active mode is bracketed: `none [integrity] confidentiality`
+    let active = lockdown.split_whitespace().find(|m| m.starts_with('['))?;
+    match active.trim_matches(|c| c == '[' || c == ']') {
+        "none" => Some(LockdownMode::None),
+        "integrity" => Some(LockdownMode::Integrity),
+        "confidentiality" => Some(LockdownMode::Confidentiality),
+        _ => None,
+    }
+}
+
+fn vulnerability_state(status: &str) -> VulnerabilityState {
+    if status.starts_with("Not affected") {
+        VulnerabilityState::NotAffected
+    } else if status.starts_with("Mitigation") {
+        VulnerabilityState::Mitigated
+    } else if status.starts_with("Vulnerable") {
+        VulnerabilityState::Vulnerable
+    } else {
+        VulnerabilityState::Unknown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixture::Fixture;
+
+    #[test]
+    fn test_taint_from_mask() {
+        let taint = Taint::from_mask(4097);
+        assert!(taint.is_tainted());
+        let codes: String = taint.flags.iter().map(|f| f.code).collect();
+        assert_eq!(codes, "PO");
+        assert!(!Taint::from_mask(0).is_tainted());
+    }
+
+    #[test]
+    fn test_security_from_root() {
+        let fixture = Fixture::new("sec");
+        let root = fixture.root();
+        fixture.write(
+            root.join("sys/kernel/security/lsm"),
+            "lockdown,capability,yama,apparmor\n",
+        );
+        fixture.write(
+            root.join("sys/kernel/security/lockdown"),
+            "none [integrity] confidentiality\n",
+        );
+        fixture.write(root.join("sys/module/apparmor/parameters/enabled"), "Y\n");
+        fixture.write(
+            root.join("sys/kernel/security/apparmor/profiles"),
+            "/usr/sbin/cupsd (enforce)\nsnap.firefox (complain)\nman_filter (enforce)\n",
+        );
+        let vulns = root.join("sys/devices/system/cpu/vulnerabilities");
+        fixture.write(vulns.join("meltdown"), "Not affected\n");
+        fixture.write(
+            vulns.join("spectre_v2"),
+            "Mitigation: Retpolines; IBPB: conditional\n",
+        );
+        fixture.write(
+            vulns.join("mds"),
+            "Vulnerable: Clear CPU buffers attempted, no microcode\n",
+        );
+        fixture.write(root.join("proc/sys/kernel/tainted"), "12288\n");
+
+        let info = from_root(root);
+        fixture.dir(root.join("sys/fs/selinux"));
+        fs::remove_file(root.join("proc/sys/kernel/tainted")).unwrap();
+        let missing = from_root(root);
+        fixture.write(root.join("sys/fs/selinux/enforce"), "0\n");
+        let permissive = from_root(root);
+
+        assert_eq!(
+            info.lsms,
+            vec!["lockdown", "capability", "yama", "apparmor"]
+        );
+        assert_eq!(info.selinux, SelinuxMode::Disabled);
+        assert_eq!(missing.selinux, SelinuxMode::Unknown);
+        assert_eq!(permissive.selinux, SelinuxMode::Permissive);
+        assert_eq!(info.lockdown, Some(LockdownMode::Integrity));
+        assert!(info.apparmor.enabled);
+        assert_eq!(info.apparmor.enforce_profiles, Some(2));
+        assert_eq!(info.apparmor.complain_profiles, Some(1));
+        let states: Vec<VulnerabilityState> =
+            info.vulnerabilities.iter().map(|v| v.state).collect();
+        assert_eq!(
+            states,
+            vec![
+                VulnerabilityState::Vulnerable,
+                VulnerabilityState::NotAffected,
+                VulnerabilityState::Mitigated
+            ]
+        );
+        let codes: String = info.taint.unwrap().flags.iter().map(|f| f.code).collect();
+        assert_eq!(codes, "OE");
+        assert_eq!(missing.taint, None);
+    }
+}
@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+const PROC_SYS: &str = "/proc/sys";
+
+/// Tunables included in `important_sysctls()`
+const IMPORTANT_SYSCTLS: [&str; 14] = [
+    "vm.swappiness",
+    "vm.overcommit_memory",
+    "vm.max_map_count",
+    "vm.dirty_ratio",
+    "vm.dirty_background_ratio",
+    "fs.file-max",
+    "fs.inotify.max_user_watches",
+    "kernel.pid_max",
+    "kernel.threads-max",
+    "kernel.randomize_va_space",
+    "net.core.somaxconn",
+    "net.ipv4.ip_forward",
+    "net.ipv4.ip_local_port_range",
+    "net.ipv4.tcp_congestion_control",
+];
+
+/// Value of a kernel tunable
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum SysctlValue {
+    Integer(i64),
+    /// Several whitespace separated integers, eg: `net.ipv4.ip_local_port_range`
+    Integers(Vec<i64>),
+    /// Anything else, including integers not fitting an `i64`
+    String(String),
+}
+
+impl SysctlValue {
+    fn parse(value: &str) -> Self {
+        let ints: Option<Vec<i64>> = value.split_whitespace().map(|v| v.parse().ok()).collect();
+        match ints {
+            Some(ints) if ints.len() == 1 => Self::Integer(ints[0]),
+            Some(ints) if !ints.is_empty() => Self::Integers(ints),
+            _ => Self::String(value.to_string()),
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Self::Integer(v) => Some(v),
+            _ => None,
+        }
+    }
+}
+
+/// A kernel tunable under `/proc/sys`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Sysctl {
+    /// Dotted name, eg: `net.ipv4.ip_forward`
+    pub name: String,
+    pub value: SysctlValue,
+    /// Value as read, whitespace trimmed
+    pub raw: String,
+}
+
+pub fn sysctl(name: &str) -> Option<Sysctl> {
+    //! Read a tunable by its dotted name like `sysctl(8)`. A `/` in a dotted
+    //! name stands for a dot inside a component (eg: `net.ipv4.conf.eth0/100.forwarding`).
+    //! Names starting with a `/` separated component use `/` as separator
+    //! (eg: `net/ipv4/conf/eth0.100/forwarding`).
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let forward = systeminfo::sysctl("net.ipv4.ip_forward").unwrap();
+    //! println!("{} = {:?}", forward.name, forward.value);
+    //! ```
+    read_at(Path::new(PROC_SYS), name)
+}
+
+pub fn sysctl_list(prefix: &str) -> Vec<Sysctl> {
+    //! Read all the readable tunables of a subtree, eg: `vm` or `net.ipv4.tcp_rmem`.
+    //! An empty prefix lists everything.
+    list_at(Path::new(PROC_SYS), prefix)
+}
+
+pub fn important_sysctls() -> Vec<Sysctl> {
+    //! Snapshot of commonly tuned settings (`vm.swappiness`, `fs.file-max`,
+    //! `kernel.pid_max`, `net.core.somaxconn`, ...) for drift reports.
+    //! Tunables missing on the running kernel are left out.
+    IMPORTANT_SYSCTLS.iter().filter_map(|n| sysctl(n)).collect()
+}
+
+fn to_path(root: &Path, name: &str) -> Option<PathBuf> {
+    let name = name.trim_matches(|c| c == '.' || c == '/');
+    // The first separator decides the format, like sysctl(8)
+    let relative = match name.find(['.', '/']).map(|i| &name[i..i + 1]) {
+        Some("/") => PathBuf::from(name),
+        _ => PathBuf::from(
+            name.chars()
+                .map(|c| match c {
+                    '.' => '/',
+                    '/' => '.',
+                    c => c,
+                })
+                .collect::<String>(),
+        ),
+    };
+    // Stay below the root, eg: `kernel/../../../etc/passwd`
+    relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+        .then(|| root.join(relative))
+}
+
+fn to_name(root: &Path, path: &Path) -> Option<String> {
+    let components: Vec<String> = path
+        .strip_prefix(root)
+        .ok()?
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().replace('.', "/"))
+        .collect();
+    Some(components.join("."))
+}
+
+fn read_at(root: &Path, name: &str) -> Option<Sysctl> {
+    read_path(root, &to_path(root, name)?)
+}
+
+fn read_path(root: &Path, path: &Path) -> Option<Sysctl> {
+    // Write-only tunables (eg: `vm.drop_caches`) fail to read
+    let raw = fs::read_to_string(path).ok()?.trim().to_string();
+    Some(Sysctl {
+        name: to_name(root, path)?,
+        value: SysctlValue::parse(&raw),
+        raw,
+    })
+}
+
+fn list_at(root: &Path, prefix: &str) -> Vec<Sysctl> {
+    let start = match to_path(root, prefix) {
+        Some(start) => start,
+        None => return Vec::new(),
+    };
+    if start.is_file() {
+        return read_path(root, &start).into_iter().collect();
+    }
+    let mut dirs = vec![start];
+    let mut sysctls = Vec::new();
+    while let Some(dir) = dirs.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            match entry.file_type() {
+                Ok(t) if t.is_dir() => dirs.push(path),
+                Ok(_) => sysctls.extend(read_path(root, &path)),
+                Err(_) => {}
+            }
+        }
+    }
+    sysctls.sort_by(|a, b| a.name.cmp(&b.name));
+    sysctls
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixture::Fixture;
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(SysctlValue::parse("60"), SysctlValue::Integer(60));
+        assert_eq!(
+            SysctlValue::parse("32768\t60999"),
+            SysctlValue::Integers(vec![32768, 60999])
+        );
+        assert_eq!(
+            SysctlValue::parse("cubic"),
+            SysctlValue::String("cubic".into())
+        );
+        assert_eq!(
+            SysctlValue::parse("18446744073692774399"),
+            SysctlValue::String("18446744073692774399".into())
+        );
+    }
+
+    #[test]
+    fn test_read_and_list() {
+        let fixture = Fixture::new("sysctl");
+        let root = fixture.root();
+        fixture.dir(root.join("net/ipv4/conf/eth0.100"));
+        fixture.dir(root.join("vm"));
+        fixture.write(root.join("net/ipv4/ip_forward"), "1\n");
+        fixture.write(root.join("net/ipv4/conf/eth0.100/forwarding"), "0\n");
+        fixture.write(root.join("vm/swappiness"), "60\n");
+
+        let forward = read_at(root, "net.ipv4.ip_forward").unwrap();
+        let vlan = read_at(root, "net/ipv4/conf/eth0.100/forwarding").unwrap();
+        let ipv4 = list_at(root, "net.ipv4");
+        let all = list_at(root, "");
+
+        assert_eq!(forward.name, "net.ipv4.ip_forward");
+        assert_eq!(forward.value.as_i64(), Some(1));
+        assert_eq!(vlan.value, SysctlValue::Integer(0));
+        assert_eq!(ipv4.len(), 2);
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[2].name, "vm.swappiness");
+        assert!(to_path(root, "vm/../../etc/passwd").is_none());
+        assert!(list_at(root, "vm/../../etc").is_empty());
+        assert!(to_path(root, "vm.//.//etc").is_none());
+
+        // Listed names read back the same tunable
+        let vlan_listed = ipv4.iter().find(|s| s.name.contains("eth0")).unwrap();
+        assert_eq!(vlan_listed.name, "net.ipv4.conf.eth0/100.forwarding");
+        assert_eq!(vlan.name, vlan_listed.name);
+        assert_eq!(
+            to_path(root, &vlan_listed.name),
+            Some(root.join("net/ipv4/conf/eth0.100/forwarding"))
+        );
+    }
+}
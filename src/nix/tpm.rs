@@ -0,0 +1,192 @@
+use crate::common::utils::read_file;
+use core::fmt::{Display, Formatter, Result};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+const SYSFS_TPM: &str = "/sys/class/tpm";
+
+/// TCG vendor IDs of TPM manufacturers
+const TPM_VENDORS: [(&str, &str); 20] = [
+    ("AMD", "AMD"),
+    ("ATML", "Atmel"),
+    ("BRCM", "Broadcom"),
+    ("CSCO", "Cisco"),
+    ("FLYS", "Flyslice Technologies"),
+    ("GOOG", "Google"),
+    ("HPE", "HPE"),
+    ("IBM", "IBM"),
+    ("IFX", "Infineon"),
+    ("INTC", "Intel"),
+    ("LEN", "Lenovo"),
+    ("MSFT", "Microsoft"),
+    ("NSM", "National Semiconductor"),
+    ("NTC", "Nuvoton"),
+    ("NTZ", "Nationz"),
+    ("QCOM", "Qualcomm"),
+    ("ROCC", "Fuzhou Rockchip"),
+    ("SNS", "Samsung"),
+    ("STM", "STMicroelectronics"),
+    ("WEC", "Winbond"),
+];
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TpmVersion {
+    Tpm12,
+    Tpm20,
+}
+
+impl Display for TpmVersion {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match *self {
+            Self::Tpm12 => write!(f, "1.2"),
+            Self::Tpm20 => write!(f, "2.0"),
+        }
+    }
+}
+
+/// A TPM registered in `/sys/class/tpm`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TpmInfo {
+    /// eg: `tpm0`
+    pub name: String,
+    pub version: Option<TpmVersion>,
+    /// TCG vendor ID, eg: `IFX`
+    pub manufacturer_id: Option<String>,
+    pub manufacturer: Option<String>,
+    pub firmware_version: Option<String>,
+    /// Character device, eg: `/dev/tpm0`
+    pub device: Option<String>,
+    /// In-kernel resource manager device (TPM 2.0 only), eg: `/dev/tpmrm0`
+    pub resource_manager: Option<String>,
+}
+
+impl TpmInfo {
+    pub fn has_resource_manager(&self) -> bool {
+        self.resource_manager.is_some()
+    }
+}
+
+pub fn tpm() -> Vec<TpmInfo> {
+    //! Get the TPMs known to the kernel. Manufacturer and firmware version
+    //! are only exposed by the kernel for TPM 1.2 devices.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! match systeminfo::tpm().first() {
+    //!     Some(tpm) => println!("TPM {:?} by {:?}", tpm.version, tpm.manufacturer),
+    //!     None => println!("no TPM"),
+    //! }
+    //! ```
+    from_dirs(Path::new(SYSFS_TPM), Path::new("/dev"))
+}
+
+fn from_dirs(class: &Path, dev: &Path) -> Vec<TpmInfo> {
+    let mut tpms: Vec<TpmInfo> = fs::read_dir(class)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .filter_map(|e| {
+                    let name = e.file_name().into_string().ok()?;
+                    let index = name.strip_prefix("tpm")?;
+                    Some(read_tpm(&e.path(), dev, &name, index))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    tpms.sort_by(|a, b| a.name.cmp(&b.name));
+    tpms
+}
+
+fn read_tpm(dir: &Path, dev: &Path, name: &str, index: &str) -> TpmInfo {
+    // TPM 1.2 only, eg:
+    // Manufacturer: 0x49465800
+    // TCG version: 1.2
+    // Firmware version: 3.19
+    let caps = read_file(dir.join("caps")).or_else(|| read_file(dir.join("device/caps")));
+    let cap = |key: &str| {
+        caps.as_ref()?.lines().find_map(|l| {
+            let (k, v) = l.split_once(':')?;
+            (k.trim() == key).then(|| v.trim().to_string())
+        })
+    };
+
+    let device = dev.join(name);
+    let resource_manager = dev.join(format!("tpmrm{}", index));
+    let resource_manager = resource_manager
+        .exists()
+        .then(|| resource_manager.display().to_string());
+
+    let version = match read_file(dir.join("tpm_version_major")).as_deref() {
+        Some("2") => Some(TpmVersion::Tpm20),
+        Some("1") => Some(TpmVersion::Tpm12),
+        // Kernels older than 5.6, only TPM 2.0 devices get a resource manager
+        _ if cap("TCG version").is_some() => Some(TpmVersion::Tpm12),
+        _ if resource_manager.is_some() => Some(TpmVersion::Tpm20),
+        _ => None,
+    };
+    let manufacturer_id = cap("Manufacturer").and_then(|m| vendor_id(&m));
+
+    TpmInfo {
+        name: name.to_string(),
+        version,
+        manufacturer: manufacturer_id.as_deref().map(|id| {
+            TPM_VENDORS
+                .iter()
+                .find(|(vendor, _)| *vendor == id)
+                .map(|(_, name)| name.to_string())
+                .unwrap_or_else(|| id.to_string())
+        }),
+        manufacturer_id,
+        firmware_version: cap("Firmware version"),
+        device: device.exists().then(|| device.display().to_string()),
+        resource_manager,
+    }
+}
+
+fn vendor_id(manufacturer: &str) -> Option<String> {
+    //! Decode the 4 ASCII characters of a TCG vendor ID, eg: `0x49465800` is `IFX`
+    let id = u32::from_str_radix(manufacturer.trim_start_matches("0x"), 16).ok()?;
+    let id: String = id
+        .to_be_bytes()
+        .iter()
+        .filter(|b| b.is_ascii_graphic())
+        .map(|&b| b as char)
+        .collect();
+    (!id.is_empty()).then_some(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixture::Fixture;
+
+    #[test]
+    fn test_tpm_from_dirs() {
+        let fixture = Fixture::new("tpm");
+        let root = fixture.root();
+        let (class, dev) = (root.join("class"), root.join("dev"));
+        fixture.dir(class.join("tpm0"));
+        fixture.dir(class.join("tpm1/device"));
+        fixture.dir(&dev);
+        fixture.write(class.join("tpm0/tpm_version_major"), "2\n");
+        fixture.write(
+            class.join("tpm1/device/caps"),
+            "Manufacturer: 0x49465800\nTCG version: 1.2\nFirmware version: 3.19\n",
+        );
+        for device in ["tpm0", "tpmrm0", "tpm1"] {
+            fixture.write(dev.join(device), "");
+        }
+
+        let tpms = from_dirs(&class, &dev);
+
+        assert_eq!(tpms.len(), 2);
+        assert_eq!(tpms[0].version, Some(TpmVersion::Tpm20));
+        assert!(tpms[0].has_resource_manager());
+        assert!(tpms[0].manufacturer.is_none());
+        assert_eq!(tpms[1].version, Some(TpmVersion::Tpm12));
+        assert_eq!(tpms[1].manufacturer_id.as_deref(), Some("IFX"));
+        assert_eq!(tpms[1].manufacturer.as_deref(), Some("Infineon"));
+        assert_eq!(tpms[1].firmware_version.as_deref(), Some("3.19"));
+        assert!(!tpms[1].has_resource_manager());
+    }
+}
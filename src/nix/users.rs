@@ -0,0 +1,370 @@
+use crate::common::utils::read_file;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, mem,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// A local account from `/etc/passwd`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct User {
+    pub name: String,
+    pub uid: u32,
+    /// Primary group
+    pub gid: u32,
+    /// Comment field, usually the full name
+    pub gecos: String,
+    pub home: String,
+    pub shell: String,
+    /// Names of the primary and supplementary groups
+    pub groups: Vec<String>,
+    /// UID outside the `UID_MIN`..`UID_MAX` range of `/etc/login.defs`,
+    /// or `nobody`
+    pub system: bool,
+}
+
+/// A local group from `/etc/group`
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Group {
+    pub name: String,
+    pub gid: u32,
+    /// Supplementary members, users with this primary group are not listed
+    pub members: Vec<String>,
+    /// GID outside the `GID_MIN`..`GID_MAX` range of `/etc/login.defs`
+    pub system: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SessionKind {
+    Login,
+    /// End of a login, the user is only known by matching the tty
+    Logout,
+    Boot,
+    Shutdown,
+    /// Runlevel change
+    RunLevel,
+}
+
+/// A `utmp`/`wtmp` record
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Session {
+    pub kind: SessionKind,
+    /// Empty for logouts, `reboot` or `shutdown` for boot records
+    pub user: String,
+    /// eg: `pts/0`, `tty1`
+    pub tty: String,
+    /// Remote host for SSH logins, kernel release for boot records
+    pub host: Option<String>,
+    pub pid: i32,
+    pub time: Option<SystemTime>,
+}
+
+pub fn users() -> Vec<User> {
+    //! Get the local accounts from `/etc/passwd`.
+    //! Accounts of NSS sources like LDAP or SSSD are not included.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! for user in systeminfo::users().iter().filter(|u| !u.system) {
+    //!     println!("{} {} {}", user.name, user.home, user.shell);
+    //! }
+    //! ```
+    users_from_root(Path::new("/"))
+}
+
+pub fn groups() -> Vec<Group> {
+    //! Get the local groups from `/etc/group`.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! for group in systeminfo::groups() {
+    //!     println!("{} {:?}", group.name, group.members);
+    //! }
+    //! ```
+    groups_from_root(Path::new("/"))
+}
+
+pub fn sessions() -> Vec<Session> {
+    //! Get the logged in users from `utmp`, like `who`.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! for session in systeminfo::sessions() {
+    //!     println!("{} {} {:?}", session.user, session.tty, session.host);
+    //! }
+    //! ```
+    sessions_from_root(Path::new("/"))
+}
+
+pub fn login_history() -> Vec<Session> {
+    //! Get the logins, logouts, boots and shutdowns recorded in `/var/log/wtmp`,
+    //! oldest first, like `last`. Systems keeping the history in `wtmpdb`
+    //! only are not supported.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! for session in systeminfo::login_history().iter().rev().take(10) {
+    //!     println!("{:?} {} {:?}", session.kind, session.user, session.time);
+    //! }
+    //! ```
+    login_history_from_root(Path::new("/"))
+}
+
+fn login_defs_range(root: &Path, min: &str, max: &str) -> (u32, u32) {
+    let defs = read_file(root.join("etc/login.defs")).unwrap_or_default();
+    let get = |key: &str| {
+        defs.lines()
+            .filter_map(|l| l.split_once(char::is_whitespace))
+            .find(|(k, _)| *k == key)
+            .and_then(|(_, v)| v.trim().parse::<u32>().ok())
+    };
+    (get(min).unwrap_or(1000), get(max).unwrap_or(60000))
+}
+
+fn users_from_root(root: &Path) -> Vec<User> {
+    let (min, max) = login_defs_range(root, "UID_MIN", "UID_MAX");
+    let groups = groups_from_root(root);
+    read_file(root.join("etc/passwd"))
+        .unwrap_or_default()
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .filter_map(|line| {
+            // name:password:uid:gid:gecos:home:shell
+            let fields: Vec<&str> = line.split(':').collect();
+            if fields.len() < 7 {
+                return None;
+            }
+            let (name, uid, gid) = (fields[0], fields[2].parse().ok()?, fields[3].parse().ok()?);
+            Some(User {
+                name: name.to_string(),
+                uid,
+                gid,
+                gecos: fields[4].to_string(),
+                home: fields[5].to_string(),
+                shell: fields[6].to_string(),
+                groups: groups
+                    .iter()
+                    .filter(|g| g.gid == gid || g.members.iter().any(|m| m == name))
+                    .map(|g| g.name.clone())
+                    .collect(),
+                system: uid < min || uid > max || name == "nobody",
+            })
+        })
+        .collect()
+}
+
+fn groups_from_root(root: &Path) -> Vec<Group> {
+    let (min, max) = login_defs_range(root, "GID_MIN", "GID_MAX");
+    read_file(root.join("etc/group"))
+        .unwrap_or_default()
+        .lines()
+        .filter(|l| !l.starts_with('#'))
+        .filter_map(|line| {
+            // name:password:gid:member,member
+            let fields: Vec<&str> = line.split(':').collect();
+            if fields.len() < 4 {
+                return None;
+            }
+            let gid = fields[2].parse().ok()?;
+            Some(Group {
+                name: fields[0].to_string(),
+                gid,
+                members: fields[3]
+                    .split(',')
+                    .filter(|m| !m.is_empty())
+                    .map(String::from)
+                    .collect(),
+                system: gid < min || gid > max || fields[0] == "nogroup",
+            })
+        })
+        .collect()
+}
+
+fn sessions_from_root(root: &Path) -> Vec<Session> {
+    ["run/utmp", "var/run/utmp"]
+        .iter()
+        .find_map(|p| fs::read(root.join(p)).ok())
+        .map(|data| parse_utmp(&data))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|s| s.kind == SessionKind::Login)
+        .collect()
+}
+
+fn login_history_from_root(root: &Path) -> Vec<Session> {
+    fs::read(root.join("var/log/wtmp"))
+        .map(|data| parse_utmp(&data))
+        .unwrap_or_default()
+}
+
+fn parse_utmp(data: &[u8]) -> Vec<Session> {
+    // The record layout differs between architectures, eg: 400 bytes on aarch64
+    let string = |chars: &[libc::c_char]| {
+        let bytes: Vec<u8> = chars
+            .iter()
+            .take_while(|&&c| c != 0)
+            .map(|&c| c as u8)
+            .collect();
+        String::from_utf8_lossy(&bytes).into_owned()
+    };
+
+    data.chunks_exact(mem::size_of::<libc::utmpx>())
+        .filter_map(|record| {
+            let ut: libc::utmpx =
+                unsafe { std::ptr::read_unaligned(record.as_ptr() as *const libc::utmpx) };
+            let user = string(&ut.ut_user);
+            let kind = match ut.ut_type {
+                libc::USER_PROCESS => SessionKind::Login,
+                libc::DEAD_PROCESS => SessionKind::Logout,
+                libc::BOOT_TIME => SessionKind::Boot,
+                libc::RUN_LVL if user == "shutdown" => SessionKind::Shutdown,
+                libc::RUN_LVL => SessionKind::RunLevel,
+                _ => return None,
+            };
+            let host = string(&ut.ut_host);
+            let seconds = ut.ut_tv.tv_sec as i64;
+            Some(Session {
+                kind,
+                user,
+                tty: string(&ut.ut_line),
+                host: (!host.is_empty()).then_some(host),
+                pid: ut.ut_pid,
+                time: (seconds > 0).then(|| UNIX_EPOCH + Duration::from_secs(seconds as u64)),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::fixture::Fixture;
+
+    #[test]
+    fn test_users_and_groups() {
+        let fixture = Fixture::new("users");
+        let root = fixture.root();
+        fixture.dir(root.join("etc"));
+        fixture.write(
+            root.join("etc/passwd"),
+            "root:x:0:0:root:/root:/bin/bash\n\
+             nobody:x:65534:65534:nobody:/nonexistent:/usr/sbin/nologin\n\
+             alice:x:1000:1000:Alice,,,:/home/alice:/bin/zsh\n",
+        );
+        fixture.write(
+            root.join("etc/group"),
+            "root:x:0:\nsudo:x:27:alice\nalice:x:1000:\nnogroup:x:65534:\n",
+        );
+        fixture.write(
+            root.join("etc/login.defs"),
+            "# comment\nUID_MIN\t\t\t 1000\n",
+        );
+        let users = users_from_root(root);
+        let groups = groups_from_root(root);
+
+        assert_eq!(users.len(), 3);
+        assert!(users[0].system && users[1].system && !users[2].system);
+        assert_eq!(users[2].groups, vec!["sudo", "alice"]);
+        assert_eq!(users[2].shell, "/bin/zsh");
+        assert_eq!(groups[1].members, vec!["alice"]);
+        assert!(groups[1].system && !groups[2].system && groups[3].system);
+    }
+
+    fn record(kind: i16, pid: i32, line: &str, user: &str, host: &str, time: i64) -> Vec<u8> {
+        let mut ut: libc::utmpx = unsafe { mem::zeroed() };
+        let copy = |dst: &mut [libc::c_char], src: &str| {
+            for (d, s) in dst.iter_mut().zip(src.bytes()) {
+                *d = s as libc::c_char;
+            }
+        };
+        ut.ut_type = kind;
+        ut.ut_pid = pid;
+        copy(&mut ut.ut_line, line);
+        copy(&mut ut.ut_user, user);
+        copy(&mut ut.ut_host, host);
+        ut.ut_tv.tv_sec = time as _;
+        let bytes = unsafe {
+            std::slice::from_raw_parts(
+                &ut as *const libc::utmpx as *const u8,
+                mem::size_of::<libc::utmpx>(),
+            )
+        };
+        bytes.to_vec()
+    }
+
+    #[test]
+    fn test_parse_utmp() {
+        let data = [
+            record(
+                libc::BOOT_TIME,
+                0,
+                "~",
+                "reboot",
+                "6.1.0-18-amd64",
+                1_700_000_000,
+            ),
+            record(libc::LOGIN_PROCESS, 500, "tty1", "LOGIN", "", 1_700_000_010),
+            record(
+                libc::USER_PROCESS,
+                812,
+                "pts/0",
+                "alice",
+                "192.0.2.7",
+                1_700_000_100,
+            ),
+            record(libc::DEAD_PROCESS, 812, "pts/0", "", "", 1_700_000_900),
+            record(
+                libc::RUN_LVL,
+                0,
+                "~~",
+                "shutdown",
+                "6.1.0-18-amd64",
+                1_700_001_000,
+            ),
+        ]
+        .concat();
+        let sessions = parse_utmp(&data);
+
+        assert_eq!(sessions.len(), 4);
+        assert_eq!(sessions[0].kind, SessionKind::Boot);
+        assert_eq!(sessions[1].user, "alice");
+        assert_eq!(sessions[1].tty, "pts/0");
+        assert_eq!(sessions[1].host.as_deref(), Some("192.0.2.7"));
+        assert_eq!(
+            sessions[1].time,
+            Some(UNIX_EPOCH + Duration::from_secs(1_700_000_100))
+        );
+        assert_eq!(sessions[2].kind, SessionKind::Logout);
+        assert_eq!(sessions[3].kind, SessionKind::Shutdown);
+    }
+
+    #[test]
+    fn test_sessions_from_root() {
+        let fixture = Fixture::new("wtmp");
+        let root = fixture.root();
+        let login = record(
+            libc::USER_PROCESS,
+            812,
+            "pts/0",
+            "alice",
+            "192.0.2.7",
+            1_700_000_100,
+        );
+        let logout = record(libc::DEAD_PROCESS, 812, "pts/0", "", "", 1_700_000_900);
+        fixture.write(
+            root.join("run/utmp"),
+            [login.clone(), logout.clone()].concat(),
+        );
+        fixture.write(root.join("var/log/wtmp"), [login, logout].concat());
+
+        let sessions = sessions_from_root(root);
+        let history = login_history_from_root(root);
+
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].user, "alice");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[1].kind, SessionKind::Logout);
+        assert!(login_history_from_root(&root.join("missing")).is_empty());
+    }
+}
@@ -0,0 +1,385 @@
+use super::dmi::Dmi;
+use crate::common::utils::read_file;
+use core::fmt::{Display, Formatter, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Hypervisor the system is running under
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub enum Virtualization {
+    /// Bare metal, no virtualization detected.
+    #[default]
+    None,
+    Kvm,
+    /// QEMU with software emulation (TCG)
+    Qemu,
+    Amazon,
+    Google,
+    Vmware,
+    /// Microsoft Hyper-V
+    Microsoft,
+    Xen,
+    /// Oracle VirtualBox
+    Oracle,
+    Bhyve,
+    Parallels,
+    Bochs,
+    Acrn,
+    PowerVm,
+    Zvm,
+    Apple,
+    Qnx,
+    /// A hypervisor was detected, but could not be identified.
+    Other(String),
+}
+
+impl Display for Virtualization {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        // Same identifiers as `systemd-detect-virt`
+        match *self {
+            Self::None => write!(f, "none"),
+            Self::Kvm => write!(f, "kvm"),
+            Self::Qemu => write!(f, "qemu"),
+            Self::Amazon => write!(f, "amazon"),
+            Self::Google => write!(f, "google"),
+            Self::Vmware => write!(f, "vmware"),
+            Self::Microsoft => write!(f, "microsoft"),
+            Self::Xen => write!(f, "xen"),
+            Self::Oracle => write!(f, "oracle"),
+            Self::Bhyve => write!(f, "bhyve"),
+            Self::Parallels => write!(f, "parallels"),
+            Self::Bochs => write!(f, "bochs"),
+            Self::Acrn => write!(f, "acrn"),
+            Self::PowerVm => write!(f, "powervm"),
+            Self::Zvm => write!(f, "zvm"),
+            Self::Apple => write!(f, "apple"),
+            Self::Qnx => write!(f, "qnx"),
+            Self::Other(ref name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Result of the virtualization detection
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct VirtualizationInfo {
+    pub virtualization: Virtualization,
+    /// The CPU reports running under a hypervisor (CPUID hypervisor bit)
+    pub hypervisor_present: bool,
+    /// Hypervisor vendor signature from CPUID leaf `0x40000000`
+    pub hypervisor_vendor: Option<String>,
+    /// What the detection was based on (eg: `dmi`, `cpuid`, `device-tree`)
+    pub source: Option<String>,
+}
+
+impl VirtualizationInfo {
+    pub fn is_virtual(&self) -> bool {
+        self.virtualization != Virtualization::None
+    }
+}
+
+/// Raw hints the detection is based on
+#[derive(Default)]
+struct Hints {
+    cpuid_hypervisor_bit: bool,
+    cpuid_vendor: Option<String>,
+    dmi: Dmi,
+    cpuinfo_hypervisor_flag: bool,
+    /// `/sys/hypervisor/type`
+    sys_hypervisor: Option<String>,
+    /// `/proc/xen/capabilities`
+    xen_capabilities: Option<String>,
+    /// `/proc/device-tree/hypervisor/compatible`
+    device_tree_hypervisor: Option<String>,
+    /// `/proc/device-tree/fw-cfg` exists
+    device_tree_fw_cfg: bool,
+    /// LPAR managed by an HMC
+    device_tree_powervm: bool,
+    /// `/proc/sysinfo` on s390x
+    s390_sysinfo: Option<String>,
+}
+
+pub fn virtualization() -> VirtualizationInfo {
+    //! Detect whether the system runs on bare metal or under a hypervisor,
+    //! similar to `systemd-detect-virt --vm`.
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let virt = systeminfo::virtualization();
+    //! println!("{} ({:?})", virt.virtualization, virt.source);
+    //! ```
+    let (cpuid_hypervisor_bit, cpuid_vendor) = cpuid_hypervisor();
+    let dt = Path::new("/proc/device-tree");
+    detect(Hints {
+        cpuid_hypervisor_bit,
+        cpuid_vendor,
+        dmi: Dmi::read(),
+        cpuinfo_hypervisor_flag: read_file("/proc/cpuinfo")
+            .map(|c| {
+                c.lines()
+                    .filter(|l| l.starts_with("flags"))
+                    .any(|l| l.split_whitespace().any(|f| f == "hypervisor"))
+            })
+            .unwrap_or_default(),
+        sys_hypervisor: read_file("/sys/hypervisor/type"),
+        xen_capabilities: read_file("/proc/xen/capabilities"),
+        device_tree_hypervisor: read_file(dt.join("hypervisor/compatible"))
+            .map(|c| c.replace('\0', " ")),
+        device_tree_fw_cfg: dt.join("fw-cfg").exists(),
+        device_tree_powervm: dt.join("ibm,partition-name").exists()
+            && dt.join("hmc-managed?").exists(),
+        s390_sysinfo: read_file("/proc/sysinfo"),
+    })
+}
+
+fn detect(hints: Hints) -> VirtualizationInfo {
+    let found = |v: Virtualization, source: &str| (v, Some(source.to_string()));
+
+    let (virtualization, source) = if hints
+        .xen_capabilities
+        .as_deref()
+        .is_some_and(|c| c.contains("control_d"))
+    {
+        // Xen dom0 is the privileged host domain, not a guest
+        (Virtualization::None, Some("xen".to_string()))
+    } else {
+        let dmi = from_dmi(&hints.dmi);
+        let cpuid = hints.cpuid_vendor.as_deref().and_then(from_cpuid_vendor);
+        match (dmi, cpuid) {
+            // QEMU firmware with KVM acceleration
+            (Some(Virtualization::Qemu), Some(cpuid)) => found(cpuid, "cpuid"),
+            (Some(dmi), _) => found(dmi, "dmi"),
+            (None, Some(cpuid)) => found(cpuid, "cpuid"),
+            (None, None) => {
+                if let Some(v) = hints.sys_hypervisor.as_deref().and_then(from_name) {
+                    found(v, "sys-hypervisor")
+                } else if let Some(v) = hints.device_tree_hypervisor.as_deref().and_then(from_name)
+                {
+                    found(v, "device-tree")
+                } else if hints.device_tree_fw_cfg {
+                    found(Virtualization::Qemu, "device-tree")
+                } else if hints.device_tree_powervm {
+                    found(Virtualization::PowerVm, "device-tree")
+                } else if let Some(v) = hints.s390_sysinfo.as_deref().and_then(from_s390_sysinfo) {
+                    found(v, "sysinfo")
+                } else if hints.cpuid_hypervisor_bit || hints.cpuinfo_hypervisor_flag {
+                    found(Virtualization::Other("vm-other".to_string()), "cpuid")
+                } else {
+                    (Virtualization::None, None)
+                }
+            }
+        }
+    };
+
+    VirtualizationInfo {
+        virtualization,
+        hypervisor_present: hints.cpuid_hypervisor_bit || hints.cpuinfo_hypervisor_flag,
+        hypervisor_vendor: hints.cpuid_vendor,
+        source,
+    }
+}
+
+fn from_cpuid_vendor(vendor: &str) -> Option<Virtualization> {
+    match vendor.trim_end_matches('\0') {
+        "KVMKVMKVM" | "Linux KVM Hv" => Some(Virtualization::Kvm),
+        "TCGTCGTCGTCG" => Some(Virtualization::Qemu),
+        "VMwareVMware" => Some(Virtualization::Vmware),
+        "Microsoft Hv" => Some(Virtualization::Microsoft),
+        "XenVMMXenVMM" => Some(Virtualization::Xen),
+        "VBoxVBoxVBox" => Some(Virtualization::Oracle),
+        "bhyve bhyve " => Some(Virtualization::Bhyve),
+        " lrpepyh  vr" | "prl hyperv  " => Some(Virtualization::Parallels),
+        "ACRNACRNACRN" => Some(Virtualization::Acrn),
+        "QNXQVMBSQG" => Some(Virtualization::Qnx),
+        "" => None,
+        other => Some(Virtualization::Other(other.trim().to_string())),
+    }
+}
+
+fn from_dmi(dmi: &Dmi) -> Option<Virtualization> {
+    let product = dmi.product_name.as_deref().unwrap_or_default();
+    [
+        &dmi.sys_vendor,
+        &dmi.product_name,
+        &dmi.product_version,
+        &dmi.board_vendor,
+        &dmi.bios_vendor,
+        &dmi.bios_version,
+    ]
+    .iter()
+    .filter_map(|s| s.as_deref())
+    .find_map(|s| match s {
+        s if s.starts_with("KVM") => Some(Virtualization::Kvm),
+        s if s.starts_with("Amazon EC2") => Some(Virtualization::Amazon),
+        s if s.starts_with("Google Compute Engine") => Some(Virtualization::Google),
+        s if s.starts_with("QEMU") => Some(Virtualization::Qemu),
+        s if s.starts_with("VMware") || s.starts_with("VMW") => Some(Virtualization::Vmware),
+        s if s.starts_with("innotek GmbH") || s.starts_with("VirtualBox") => {
+            Some(Virtualization::Oracle)
+        }
+        s if s.starts_with("Oracle Corporation") && product.starts_with("VirtualBox") => {
+            Some(Virtualization::Oracle)
+        }
+        s if s.starts_with("Xen") => Some(Virtualization::Xen),
+        s if s.starts_with("Bochs") => Some(Virtualization::Bochs),
+        s if s.starts_with("Parallels") => Some(Virtualization::Parallels),
+        s if s.starts_with("BHYVE") => Some(Virtualization::Bhyve),
+        s if s.starts_with("Hyper-V") => Some(Virtualization::Microsoft),
+        s if s.starts_with("Microsoft Corporation") && product == "Virtual Machine" => {
+            Some(Virtualization::Microsoft)
+        }
+        s if s.starts_with("Apple Virtualization") => Some(Virtualization::Apple),
+        _ => None,
+    })
+}
+
+fn from_name(name: &str) -> Option<Virtualization> {
+    //! Hypervisor name from `/sys/hypervisor/type` or a device-tree compatible string
+    let name = name.to_lowercase();
+    if name.contains("kvm") {
+        Some(Virtualization::Kvm)
+    } else if name.contains("xen") {
+        Some(Virtualization::Xen)
+    } else if name.contains("vmware") {
+        Some(Virtualization::Vmware)
+    } else if name.contains("qemu") {
+        Some(Virtualization::Qemu)
+    } else if name.is_empty() {
+        None
+    } else {
+        Some(Virtualization::Other(name))
+    }
+}
+
+fn from_s390_sysinfo(sysinfo: &str) -> Option<Virtualization> {
+    sysinfo
+        .lines()
+        .find(|l| l.starts_with("VM00 Control Program:"))
+        .map(|l| {
+            if l.contains("z/VM") {
+                Virtualization::Zvm
+            } else if l.contains("KVM") {
+                Virtualization::Kvm
+            } else {
+                Virtualization::Other("vm-other".to_string())
+            }
+        })
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[allow(unused_unsafe)]
+fn cpuid_hypervisor() -> (bool, Option<String>) {
+    //! Returns the hypervisor bit (leaf 1, ECX bit 31) and,
+    //! when set, the vendor signature of leaf `0x40000000`.
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::__cpuid;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::__cpuid;
+
+    let leaf1 = unsafe { __cpuid(1) };
+    if leaf1.ecx & (1 << 31) == 0 {
+        return (false, None);
+    }
+    let leaf = unsafe { __cpuid(0x4000_0000) };
+    let vendor: Vec<u8> = [leaf.ebx, leaf.ecx, leaf.edx]
+        .iter()
+        .flat_map(|r| r.to_le_bytes())
+        .collect();
+    let vendor = String::from_utf8_lossy(&vendor)
+        .trim_end_matches('\0')
+        .to_string();
+    (
+        true,
+        if vendor.is_empty() {
+            None
+        } else {
+            Some(vendor)
+        },
+    )
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn cpuid_hypervisor() -> (bool, Option<String>) {
+    (false, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_from_cpuid() {
+        let info = detect(Hints {
+            cpuid_hypervisor_bit: true,
+            cpuid_vendor: Some("KVMKVMKVM".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(info.virtualization, Virtualization::Kvm);
+        assert_eq!(info.source.as_deref(), Some("cpuid"));
+        assert!(info.is_virtual());
+    }
+
+    #[test]
+    fn test_detect_dmi_precedence() {
+        let mut hints = Hints {
+            cpuid_hypervisor_bit: true,
+            cpuid_vendor: Some("KVMKVMKVM".to_string()),
+            ..Default::default()
+        };
+        hints.dmi.sys_vendor = Some("Amazon EC2".to_string());
+        assert_eq!(detect(hints).virtualization, Virtualization::Amazon);
+
+        let mut hints = Hints {
+            cpuid_vendor: Some("KVMKVMKVM".to_string()),
+            ..Default::default()
+        };
+        hints.dmi.sys_vendor = Some("QEMU".to_string());
+        assert_eq!(detect(hints).virtualization, Virtualization::Kvm);
+
+        let mut hints = Hints::default();
+        hints.dmi.sys_vendor = Some("innotek GmbH".to_string());
+        assert_eq!(detect(hints).virtualization, Virtualization::Oracle);
+
+        let mut hints = Hints::default();
+        hints.dmi.product_name = Some("Google Compute Engine".to_string());
+        assert_eq!(detect(hints).virtualization, Virtualization::Google);
+
+        // Physical Google hardware, eg: a Chromebook
+        let mut hints = Hints::default();
+        hints.dmi.sys_vendor = Some("Google".to_string());
+        hints.dmi.board_vendor = Some("Google".to_string());
+        assert_eq!(detect(hints).virtualization, Virtualization::None);
+    }
+
+    #[test]
+    fn test_detect_fallbacks() {
+        let info = detect(Hints::default());
+        assert_eq!(info.virtualization, Virtualization::None);
+        assert!(!info.is_virtual());
+
+        let dom0 = detect(Hints {
+            xen_capabilities: Some("control_d".to_string()),
+            sys_hypervisor: Some("xen".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(dom0.virtualization, Virtualization::None);
+
+        let domu = detect(Hints {
+            sys_hypervisor: Some("xen".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(domu.virtualization, Virtualization::Xen);
+
+        let arm = detect(Hints {
+            device_tree_hypervisor: Some("linux,kvm".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(arm.virtualization, Virtualization::Kvm);
+        assert_eq!(arm.virtualization.to_string(), "kvm");
+
+        let zvm = detect(Hints {
+            s390_sysinfo: Some("VM00 Control Program: z/VM    7.2.0".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(zvm.virtualization, Virtualization::Zvm);
+    }
+}
@@ -0,0 +1,125 @@
+use crate::common::utils::read_file;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Virtual memory counters from `/proc/vmstat`.
+/// Event counters are cumulative since boot, use [`VmStat::delta`]
+/// to get the activity between two readings. Most `nr_*` entries are
+/// gauges of the current state, eg: `nr_free_pages`.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct VmStat {
+    /// Minor and major page faults
+    pub page_faults: u64,
+    pub major_page_faults: u64,
+    /// Pages read from disk, in KiB
+    pub page_in: u64,
+    /// Pages written to disk, in KiB
+    pub page_out: u64,
+    /// Pages swapped in
+    pub swap_in: u64,
+    /// Pages swapped out
+    pub swap_out: u64,
+    pub oom_kills: u64,
+    pub compact_stall: u64,
+    pub compact_fail: u64,
+    pub compact_success: u64,
+    /// Every counter and gauge of `/proc/vmstat`, by name
+    pub counters: BTreeMap<String, u64>,
+}
+
+pub fn vmstat() -> Option<VmStat> {
+    //! Get the virtual memory statistics from `/proc/vmstat`
+    //! ## Example Usage:
+    //! ```ignore
+    //! use systeminfo;
+    //! let before = systeminfo::vmstat().unwrap();
+    //! std::thread::sleep(std::time::Duration::from_secs(1));
+    //! let after = systeminfo::vmstat().unwrap();
+    //! println!("{} pages swapped out", after.delta(&before).swap_out);
+    //! ```
+    read_file("/proc/vmstat").map(|s| parse_vmstat(&s))
+}
+
+impl VmStat {
+    pub fn delta(&self, prev: &Self) -> Self {
+        //! Difference of the event counters between this reading and an
+        //! earlier one. Gauges keep the value of this reading.
+        let counters: BTreeMap<String, u64> = self
+            .counters
+            .iter()
+            .map(|(k, &v)| {
+                if is_gauge(k) {
+                    return (k.clone(), v);
+                }
+                let prev = prev.counters.get(k).copied().unwrap_or_default();
+                (k.clone(), v.saturating_sub(prev))
+            })
+            .collect();
+        Self::from_counters(counters)
+    }
+
+    fn from_counters(counters: BTreeMap<String, u64>) -> Self {
+        let get = |k: &str| counters.get(k).copied().unwrap_or_default();
+        Self {
+            page_faults: get("pgfault"),
+            major_page_faults: get("pgmajfault"),
+            page_in: get("pgpgin"),
+            page_out: get("pgpgout"),
+            swap_in: get("pswpin"),
+            swap_out: get("pswpout"),
+            oom_kills: get("oom_kill"),
+            compact_stall: get("compact_stall"),
+            compact_fail: get("compact_fail"),
+            compact_success: get("compact_success"),
+            counters,
+        }
+    }
+}
+
+fn is_gauge(name: &str) -> bool {
+    //! Entries holding a current amount rather than counting events
+    const NR_COUNTERS: [&str; 4] = [
+        "nr_dirtied",
+        "nr_written",
+        "nr_foll_pin_acquired",
+        "nr_foll_pin_released",
+    ];
+    (name.starts_with("nr_") && !NR_COUNTERS.contains(&name)) || name == "workingset_nodes"
+}
+
+fn parse_vmstat(content: &str) -> VmStat {
+    VmStat::from_counters(
+        content
+            .lines()
+            .filter_map(|l| l.split_once(' '))
+            .filter_map(|(k, v)| Some((k.to_string(), v.trim().parse().ok()?)))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_vmstat_delta() {
+        let prev = parse_vmstat(
+            "pgfault 1000\npgmajfault 5\npswpout 10\noom_kill 0\nnr_free_pages 150\nnr_dirty 20\nnr_dirtied 4\n",
+        );
+        let cur = parse_vmstat(
+            "pgfault 1500\npgmajfault 7\npswpout 12\noom_kill 1\nnr_free_pages 99\nnr_dirty 35\nnr_dirtied 9\n",
+        );
+        assert_eq!(cur.page_faults, 1500);
+        assert_eq!(cur.counters["nr_free_pages"], 99);
+
+        let delta = cur.delta(&prev);
+        assert_eq!(delta.page_faults, 500);
+        assert_eq!(delta.major_page_faults, 2);
+        assert_eq!(delta.swap_out, 2);
+        assert_eq!(delta.oom_kills, 1);
+        assert_eq!(delta.compact_stall, 0);
+        assert_eq!(delta.counters["nr_free_pages"], 99);
+        assert_eq!(delta.counters["nr_dirty"], 35);
+        assert_eq!(delta.counters["nr_dirtied"], 5);
+    }
+}
@@ -56,6 +56,7 @@ pub fn from_system_os() -> SystemOS {
         architecture: os_arch().to_string(),
         hostname: hostname().unwrap_or_default(),
         ip_address: get_local_ip().unwrap_or_default(),
+        ..Default::default()
     }
 }
 